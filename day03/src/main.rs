@@ -2,10 +2,8 @@ use anyhow::Result;
 use two_space::*;
 
 fn main() -> Result<()> {
-    let map: Vec<_> = INPUT
-        .lines()
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect::<Vec<_>>();
+    let map: DenseStore<char> = INPUT.parse().expect("Bad map");
+    let map = Wrapped::repeat_x(map);
 
     let slope: Point = (3, 1).into();
 
@@ -24,18 +22,14 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn count_trees(map: &Vec<Vec<char>>, slope: Point) -> i32 {
+fn count_trees<G: Grid<Glyph = char>>(map: &G, slope: Point) -> i32 {
     let mut pos: Point = (0, 0).into();
     let mut tree_count = 0;
-    let mut i = 0;
-    while pos.y < map.len() as isize {
-        let row = &map[pos.y as usize];
-        let ch = row[pos.x as usize % row.len()];
+    while let Some(ch) = map.at(pos) {
         if ch.eq(&'#') {
             tree_count += 1
         }
-        i += 1;
-        pos = slope * i;
+        pos += slope;
     }
     tree_count
 }
@@ -33,6 +33,7 @@ fn main() -> Result<()> {
             Instruction::Acc(_) => continue,
             Instruction::Jmp(x) => Instruction::Nop(*x),
             Instruction::Nop(x) => Instruction::Jmp(*x),
+            Instruction::Snd(_) | Instruction::Rcv(_) => continue,
         };
 
         let mut m2 = Machine::new();
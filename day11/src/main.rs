@@ -1,5 +1,11 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::time::Duration;
+
 use anyhow::Result;
-use two_space::{DenseGrid, Grid, Point};
+use two_space::{
+    Adjacency, Automaton, DenseStore, GifRecorder, Image, Neighborhood, Player, Rgb, Visibility,
+};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Tile {
@@ -29,52 +35,36 @@ impl From<Tile> for char {
     }
 }
 
+/// Pass `--watch` to watch each part's seating settle in the terminal, or one or two file names
+/// to record them as animated GIFs.
 fn main() -> Result<()> {
-    let mut grid: DenseGrid<Tile> = two_space::DenseGrid::new(INPUT);
-
-    loop {
-        let last_grid = grid;
-        grid = cycle(&last_grid);
-        if last_grid == grid {
-            break;
-        }
-    }
-
-    println!("{}", grid);
+    let (watch, gifs): (Vec<String>, Vec<String>) =
+        std::env::args().skip(1).partition(|a| a == "--watch");
+    let watch = !watch.is_empty();
+    let grid: DenseStore<Tile> = INPUT.parse().expect("Bad seating");
+    let seats = Visibility::new(&grid, Neighborhood::King, |&t| t != Tile::FLOOR);
 
-    let filled: usize = grid
-        .enumerate_tiles()
-        .filter(|(g, _)| *g == Tile::FILLED)
-        .count();
-
-    println!("{}", filled);
-
-    let mut grid_two: DenseGrid<Tile> = two_space::DenseGrid::new(INPUT);
-
-    loop {
-        let last_grid = grid_two;
-        grid_two = cycle_ranged(&last_grid);
-        if last_grid == grid_two {
-            break;
-        }
-    }
+    let mut adjacent = Automaton::new(grid.clone(), Neighborhood::King, seating(4)).incremental();
+    settle(&mut adjacent, watch, gifs.first())?;
 
-    println!("{}", grid_two);
+    println!("{}", adjacent.grid());
+    println!("{}", count_filled(adjacent.grid()));
 
-    let filled_two: usize = grid_two
-        .enumerate_tiles()
-        .filter(|(g, _)| *g == Tile::FILLED)
-        .count();
+    let mut ranged = Automaton::new(grid, seats, seating(5)).incremental();
+    settle(&mut ranged, watch, gifs.get(1))?;
 
-    println!("{}", filled_two);
+    println!("{}", ranged.grid());
+    println!("{}", count_filled(ranged.grid()));
 
     Ok(())
 }
 
-fn cycle(grid: &DenseGrid<Tile>) -> DenseGrid<Tile> {
-    grid.transform(|g, p| {
-        let count = count_filled(grid, p);
-        match g {
+/// The seating rule, where people leave a filled seat once `tolerance` of their neighbours are
+/// filled.
+fn seating(tolerance: usize) -> impl FnMut(&Tile, &mut dyn Iterator<Item = &Tile>) -> Tile {
+    move |t, neighbors| {
+        let count = neighbors.filter(|&&n| n == Tile::FILLED).count();
+        match t {
             Tile::FLOOR => Tile::FLOOR,
             Tile::SEAT => {
                 if count == 0 {
@@ -84,81 +74,67 @@ fn cycle(grid: &DenseGrid<Tile>) -> DenseGrid<Tile> {
                 }
             }
             Tile::FILLED => {
-                if count >= 4 {
+                if count >= tolerance {
                     Tile::SEAT
                 } else {
                     Tile::FILLED
                 }
             }
         }
-    })
+    }
 }
 
-fn cycle_ranged(grid: &DenseGrid<Tile>) -> DenseGrid<Tile> {
-    grid.transform(|g, p| {
-        let count = count_filled_ranged(grid, p);
-        match g {
-            Tile::FLOOR => Tile::FLOOR,
-            Tile::SEAT => {
-                if count == 0 {
-                    Tile::FILLED
-                } else {
-                    Tile::SEAT
-                }
-            }
-            Tile::FILLED => {
-                if count >= 5 {
-                    Tile::SEAT
-                } else {
-                    Tile::FILLED
-                }
-            }
-        }
-    })
+fn colour(t: &Tile) -> Rgb {
+    match t {
+        Tile::FLOOR => Rgb(40, 40, 40),
+        Tile::SEAT => Rgb(90, 160, 90),
+        Tile::FILLED => Rgb(220, 80, 60),
+    }
 }
 
-fn count_filled(grid: &DenseGrid<Tile>, loc: Point) -> u32 {
-    [
-        (-1 as isize, -1 as isize),
-        (0, -1),
-        (1, -1),
-        (-1, 0),
-        (1, 0),
-        (-1, 1),
-        (0, 1),
-        (1, 1),
-    ]
-    .iter()
-    .map(|p| Point::from(p))
-    .map(|offset| offset + loc)
-    .map(|p| grid.at(p).unwrap_or(&Tile::FLOOR))
-    .filter(|&t| t == &Tile::FILLED)
-    .count() as u32
+/// Steps until the seating settles, after playing it on screen or recording it if asked.
+fn settle<N, R>(
+    seating: &mut Automaton<DenseStore<Tile>, N, R>,
+    watch: bool,
+    gif: Option<&String>,
+) -> Result<()>
+where
+    N: Adjacency,
+    R: FnMut(&Tile, &mut dyn Iterator<Item = &Tile>) -> Tile,
+{
+    if watch {
+        Player::new(Duration::from_millis(100)).play(seating, |&t| (char::from(t), colour(&t)))?;
+    } else if let Some(path) = gif {
+        record_until_stable(seating, path)?;
+    }
+    // Quitting the player early leaves the rest to do.
+    seating.run_until_stable();
+    Ok(())
 }
 
-fn count_filled_ranged(grid: &DenseGrid<Tile>, loc: Point) -> u32 {
-    [
-        (-1 as isize, -1 as isize),
-        (0, -1),
-        (1, -1),
-        (-1, 0),
-        (1, 0),
-        (-1, 1),
-        (0, 1),
-        (1, 1),
-    ]
-    .iter()
-    .map(|p| Point::from(p))
-    .map(|offset| {
-        let mut loc = offset + loc;
-        while let Some(Tile::FLOOR) = grid.at(loc) {
-            loc = offset + loc
-        }
-        loc
-    })
-    .map(|p| grid.at(p).unwrap_or(&Tile::FLOOR))
-    .filter(|&t| t == &Tile::FILLED)
-    .count() as u32
+/// Steps until the seating settles, writing every generation as a frame.
+fn record_until_stable<N, R>(
+    seating: &mut Automaton<DenseStore<Tile>, N, R>,
+    path: &str,
+) -> Result<()>
+where
+    N: Adjacency,
+    R: FnMut(&Tile, &mut dyn Iterator<Item = &Tile>) -> Tile,
+{
+    let render = |grid: &DenseStore<Tile>| Image::render(grid, 4, colour);
+    let first = render(seating.grid());
+    let out = BufWriter::new(File::create(path)?);
+    let mut gif = GifRecorder::new(out, first.width(), first.height(), 10)?;
+    gif.frame(&first)?;
+    while seating.step() > 0 {
+        gif.frame(&render(seating.grid()))?;
+    }
+    gif.finish()?;
+    Ok(())
+}
+
+fn count_filled(grid: &DenseStore<Tile>) -> usize {
+    grid.tiles().filter(|(g, _)| **g == Tile::FILLED).count()
 }
 
 const INPUT: &str = r#"LLLLLL.LLLL..LLLLLL.LLLLLLLLLLLLLLLLLLLLL.LLLLLL.LLLLLLLL.LLLLL.LLLLLL.L.LL.LLLLLL.LLLLLLLLLLLLLLLL
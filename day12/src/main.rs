@@ -1,12 +1,11 @@
 use anyhow::Result;
 use std::str::FromStr;
-use two_space::Point;
+use two_space::{bounding_box, Point, Rgb, Svg};
 
 const NORTH: Point = Point { x: 0, y: 1 };
 const EAST: Point = Point { x: 1, y: 0 };
 const SOUTH: Point = Point { x: 0, y: -1 };
 const WEST: Point = Point { x: -1, y: 0 };
-const HEADINGS: [Point; 4] = [NORTH, EAST, SOUTH, WEST];
 
 trait Nav {
     fn go(&mut self, abs_offset: Point);
@@ -41,23 +40,7 @@ impl Nav for Ship {
     }
 
     fn turn(&mut self, amount: isize) {
-        let offset = match amount {
-            90 => 1,
-            180 => 2,
-            270 => 3,
-            -90 => 3,
-            -180 => 2,
-            -270 => 1,
-            _ => panic!("Bad turn"),
-        };
-
-        if let Some((i, _)) = HEADINGS
-            .iter()
-            .enumerate()
-            .find(|(_, h)| *h == &self.heading)
-        {
-            self.heading = HEADINGS[(i + offset) % HEADINGS.len()]
-        }
+        self.heading = self.heading.rotate_deg(-amount);
     }
 }
 
@@ -136,25 +119,71 @@ impl FromStr for Maneuver {
 }
 
 fn main() -> Result<()> {
+    let svgs: Vec<String> = std::env::args().skip(1).collect();
     let input: Vec<Maneuver> = INPUT.lines().map(|l| l.parse().unwrap()).collect();
 
     let mut ship = Ship::new();
+    let mut route = vec![ship.position];
 
-    input.iter().for_each(|m| m.exec(&mut ship));
+    for m in input.iter() {
+        m.exec(&mut ship);
+        route.push(ship.position);
+    }
 
     println!(
         "Manhattan dist {}",
         &ship.position.manhattan_dist(&(0, 0).into())
     );
 
+    if let Some(path) = svgs.first() {
+        plot(path, &[(&route, Rgb(0, 0, 192))])?;
+    }
+
     let mut way_ship = WaypointShip::new();
+    let mut way_route = vec![way_ship.position];
+    let mut waypoints = vec![way_ship.position + way_ship.waypoint];
 
-    input.iter().for_each(|m| m.exec(&mut way_ship));
+    for m in input.iter() {
+        m.exec(&mut way_ship);
+        way_route.push(way_ship.position);
+        waypoints.push(way_ship.position + way_ship.waypoint);
+    }
 
     println!(
         "Manhattan dist {}",
         &way_ship.position.manhattan_dist(&(0, 0).into())
     );
+
+    if let Some(path) = svgs.get(1) {
+        plot(
+            path,
+            &[(&waypoints, Rgb(192, 128, 0)), (&way_route, Rgb(0, 0, 192))],
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes each route as a line, with north up, marking where the first one starts and ends.
+fn plot(path: &str, routes: &[(&[Point], Rgb)]) -> Result<()> {
+    let all = bounding_box(routes.iter().flat_map(|(r, _)| r.iter().copied()));
+    let size = (all.width().max(all.height()) as f64 / 50.0).max(1.0);
+
+    let mut svg = Svg::y_up();
+    for (route, colour) in routes {
+        svg.polyline(route.iter().copied(), *colour);
+    }
+    if let (Some(&start), Some(&end)) = (routes[0].0.first(), routes[0].0.last()) {
+        svg.point(start, size / 2.0, Rgb(0, 160, 0))
+            .point(end, size / 2.0, Rgb(192, 0, 0))
+            .label(
+                start + Point::new(0, size as isize),
+                "start",
+                size,
+                Rgb::BLACK,
+            )
+            .label(end + Point::new(0, size as isize), "end", size, Rgb::BLACK);
+    }
+    std::fs::write(path, svg.to_string())?;
     Ok(())
 }
 
@@ -7,6 +7,7 @@ use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use itertools::Itertools;
 use num::integer::Roots;
+use two_space::{stitch, DenseStore, Grid, Pattern, Point, Rect, Side, View};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 enum Pixel {
@@ -38,23 +39,21 @@ impl Display for Pixel {
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct Tile {
     id: u64,
-    image: Vec<Vec<Pixel>>,
+    image: DenseStore<Pixel>,
 }
 
 impl Tile {
     fn borders(&self) -> impl Iterator<Item=Vec<Pixel>> {
-        let border_list = vec![
-            self.image[0].clone(),
-            self.image.iter().map(|row| row[row.len()-1]).collect(),
-            self.image[self.image.len()-1].clone(),
-            self.image.iter().map(|row| row[0]).collect(),
-        ];
+        let view = View::new(self);
+        let border_list: Vec<Vec<Pixel>> = Side::ALL.iter()
+            .map(|&side| view.border(side).copied().collect())
+            .collect();
         border_list.into_iter()
     }
 
     fn flip(&self) -> Tile {
         let mut img = self.image.clone();
-        img.reverse();
+        img.flip_y();
         Tile {
             id: self.id,
             image: img
@@ -63,63 +62,33 @@ impl Tile {
 
     fn rotate_cw(&self) -> Tile {
         let mut img = self.image.clone();
-        let size = img.len();
-        for i in 0..size {
-            for j in 0..size {
-                img[i][j] = self.image[size-j-1][i]
-            }
-        }
+        img.rotate_cw();
         Tile {
             id: self.id,
             image: img
         }
     }
 
-    fn trimmed(&self) -> Tile {
-        let mut img = Vec::from(&self.image[1..self.image.len()-1]);
-        img.iter_mut().for_each(|row| {
-            row.pop();
-            row.remove(0);
-        });
-
-        Tile {
-            id: self.id,
-            image: img
-        }
+    fn count_on(&self) -> usize {
+        self.image.tiles()
+            .filter(|(p, _)| **p == Pixel::On)
+            .count()
     }
+}
 
-    fn find(&self, needle: &Tile) -> Vec<(usize, usize)> {
-        let mut ret = Vec::new();
-
-        let h = needle.image.len();
-        let w = needle.image[0].len();
-        for i in 0..self.image.len()-h {
-            for j in 0..self.image[i].len()-w {
-                let mut found = true;
-                for s in 0..h {
-                    for t in 0..w {
-                        if needle.image[s][t] == Pixel::On && self.image[i+s][j+t] == Pixel::Off {
-                            found = false;
-                            break;
-                        }
-                    }
-                    if !found {
-                        break;
-                    }
-                }
-                if found {
-                    ret.push((i,j))
-                }
-            }
-        }
+impl Grid for Tile {
+    type Glyph = Pixel;
 
-        ret
+    fn at(&self, p: Point) -> Option<&Pixel> {
+        self.image.at(p)
     }
 
-    fn count_on(&self) -> usize {
-        self.image.iter()
-            .map(|row| row.iter().filter(|&p| *p == Pixel::On).count())
-            .sum()
+    fn at_mut(&mut self, p: Point) -> Option<&mut Pixel> {
+        self.image.at_mut(p)
+    }
+
+    fn bounds(&self) -> Rect {
+        self.image.bounds()
     }
 }
 
@@ -141,14 +110,14 @@ impl FromStr for Tile {
 
         Ok(Tile {
             id: caps[1].parse()?,
-            image: grid,
+            image: DenseStore::new(&grid),
         })
     }
 }
 
 impl Display for Tile {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let s = self.image.iter()
+        let s = self.image.rows()
             .map(|row| row.iter().map(|p| p.to_string()).collect::<String>())
             .join("\n");
         write!(f, "{}", &s)
@@ -257,42 +226,29 @@ fn main() -> Result<()> {
         }
     }
 
-    let full_image = grid.iter().flat_map(|tile_row| {
-        let trimmed_row = tile_row.iter().map(|t| t.trimmed()).collect::<Vec<_>>();
-        (0..trimmed_row[0].image.len()).map(move |i| {
-            trimmed_row.iter()
-                .map(|t| &t.image[i])
-                .flatten()
-                .copied()
-                .collect::<Vec<Pixel>>()
-        })
-    }).collect::<Vec<Vec<Pixel>>>();
-    let big_tile = Tile {
+    let trimmed = grid.iter()
+        .map(|tile_row| tile_row.iter().map(|t| View::new(t).inner(1)).collect())
+        .collect::<Vec<Vec<_>>>();
+    let mut big_tile = Tile {
         id: 0,
-        image: full_image,
+        image: stitch(&trimmed),
     };
 
-    let seamonster_pat = r"Tile 1:
-..................#.
+    let seamonster_pat = r"..................#.
 #....##....##....###
 .#..#..#..#..#..#...";
 
-    let monster = Tile::from_str(seamonster_pat).unwrap();
+    let monster = Pattern::parse(seamonster_pat, |ch| match ch {
+        '#' => Some(Pixel::On),
+        _ => None,
+    });
 
-    let big_tile = rot_flip(&big_tile).iter()
-        .filter(|&t| {
-            !t.find(&monster)
-                .is_empty()
-        })
-        .next().unwrap().clone();
+    let positions = monster.find_oriented(&big_tile);
+    monster.stamp(&mut big_tile, &positions, Pixel::Marked);
 
     println!("\nMap:\n{}\n", &big_tile);
 
-    let monster_count = monster.count_on();
-
-    let positions = big_tile.find(&monster);
-    let map_count = big_tile.count_on();
-    dbg!(map_count - monster_count*positions.len());
+    dbg!(big_tile.count_on());
 
     Ok(())
 }
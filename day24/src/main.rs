@@ -1,118 +1,14 @@
-use std::collections::{HashMap, HashSet};
-use std::iter;
-use std::ops::{Add};
-use std::str::FromStr;
+use std::collections::HashMap;
 
 use anyhow::Result;
+use two_space::{
+    parse_path, Automaton, Hex, HexDirection, Orientation, Point, PointyDirection, Rgb,
+    SparseGrid, Svg,
+};
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
-struct HexVector {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl HexVector {
-    fn neighbors(&self) -> impl Iterator<Item=HexVector> + '_ {
-        const DIRS: [HexVector; 6] = [
-            HexVector {
-                x: 1,
-                y: -1,
-                z: 0,
-            },
-            HexVector {
-                x: -1,
-                y: 1,
-                z: 0,
-            },
-            HexVector {
-                x: 0,
-                y: -1,
-                z: 1,
-            },
-            HexVector {
-                x: -1,
-                y: 0,
-                z: 1,
-            },
-            HexVector {
-                x: 1,
-                y: 0,
-                z: -1,
-            },
-            HexVector {
-                x: 0,
-                y: 1,
-                z: -1,
-            }
-        ];
-
-        DIRS.iter()
-            .map(move |d| d + self)
-    }
-}
-
-impl Add for &HexVector {
-    type Output = HexVector;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        HexVector {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
-}
-
-impl FromStr for HexVector {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pattern = regex::Regex::new(r"e|w|se|sw|ne|nw").unwrap();
-        let mut ret = HexVector {
-            x: 0,
-            y: 0,
-            z: 0,
-        };
-        for cap in pattern.captures_iter(s) {
-            let dir = match &cap[0] {
-                "e" => HexVector {
-                    x: 1,
-                    y: -1,
-                    z: 0,
-                },
-                "w" => HexVector {
-                    x: -1,
-                    y: 1,
-                    z: 0,
-                },
-                "se" => HexVector {
-                    x: 0,
-                    y: -1,
-                    z: 1,
-                },
-                "sw" => HexVector {
-                    x: -1,
-                    y: 0,
-                    z: 1,
-                },
-                "ne" => HexVector {
-                    x: 1,
-                    y: 0,
-                    z: -1,
-                },
-                "nw" => HexVector {
-                    x: 0,
-                    y: 1,
-                    z: -1,
-                },
-                _ => panic!("bad direction")
-            };
-
-            ret = &ret + &dir;
-        }
-        Ok(ret)
-    }
+fn parse_tile(s: &str) -> Result<Hex> {
+    let path: Vec<PointyDirection> = parse_path(s)?;
+    Ok(path.into_iter().fold(Hex::default(), Hex::step))
 }
 
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
@@ -121,57 +17,39 @@ enum Color {
     BLACK,
 }
 
-struct Board {
-    state: HashMap<HexVector, Color>,
+/// Tiles are stored by their axial `q` and `r`, so the floor can be a `SparseGrid` of the black
+/// ones.
+fn to_point(hv: Hex) -> Point {
+    Point::new(hv.q, hv.r)
 }
 
-impl Board {
-    fn color_of(&self, hv: &HexVector) -> Color {
-        *self.state.get(hv).unwrap_or(&Color::WHITE)
-    }
-
-    fn neighbor_black_count(&self, hv: &HexVector) -> u32 {
-        hv.neighbors()
-            .filter(|neighbor| self.color_of(&neighbor)==Color::BLACK)
-            .count() as u32
-    }
-
-    fn round(&mut self) {
-        let to_check: HashSet<HexVector> = self.state.iter()
-            .filter(|(_, &color)| color == Color::BLACK)
-            .flat_map(|(hv, _)|
-                hv.neighbors()
-                    .map(|neighbor| neighbor)
-                    .chain(iter::once(hv.clone()))
-            )
-            .collect();
+fn to_hex(p: Point) -> Hex {
+    Hex::new(p.x, p.y)
+}
 
-        let changes: Vec<_> = to_check.iter()
-            .map(|hv| (hv, self.color_of(hv), self.neighbor_black_count(hv)))
-            .filter_map(|(hv, current_color, blacks)| {
-                if current_color == Color::WHITE && blacks == 2 {
-                    Some((hv, Color::BLACK))
-                } else if current_color == Color::BLACK && (blacks == 0 || blacks > 2) {
-                    Some((hv, Color::WHITE))
-                } else {
-                    None
-                }
-            })
-            .collect();
-        for (hv, color) in changes {
-            self.state.insert(hv.clone(), color);
-        }
+fn flip(color: &Color, neighbors: &mut dyn Iterator<Item = &Color>) -> Color {
+    let blacks = neighbors.filter(|&&c| c == Color::BLACK).count();
+    if *color == Color::WHITE && blacks == 2 {
+        Color::BLACK
+    } else if *color == Color::BLACK && (blacks == 0 || blacks > 2) {
+        Color::WHITE
+    } else {
+        *color
     }
+}
 
-    fn blacks(&self) -> u32 {
-        self.state.values()
-            .filter(|&c| *c == Color::BLACK)
-            .count() as u32
+/// Draws the black tiles over a pink reference tile at the origin.
+fn to_svg(floor: &SparseGrid<Color>) -> Svg {
+    let mut svg = Svg::new();
+    svg.hex(Hex::default(), Orientation::Pointy, 10.0, Rgb(255, 160, 160));
+    for (p, _) in floor.occupied() {
+        svg.hex(to_hex(p), Orientation::Pointy, 10.0, Rgb(32, 32, 32));
     }
+    svg
 }
 
 fn main() -> Result<()> {
-    let input: Vec<HexVector> = INPUT.lines().map(|l| l.parse().unwrap()).collect();
+    let input: Vec<Hex> = INPUT.lines().map(parse_tile).collect::<Result<_>>()?;
 
     let tile_flips = input.iter()
         .fold(HashMap::new(), |mut acc, v| {
@@ -186,17 +64,22 @@ fn main() -> Result<()> {
 
     dbg!(blacks);
 
-    let mut board = Board{
-        state: tile_flips.iter()
-            .map(|(&k, v)| (k.clone(), if v % 2 == 1 { Color::BLACK } else { Color::WHITE}))
-            .collect()
-    };
-
-    for _ in 0..100 {
-        board.round();
+    let mut floor = SparseGrid::new(Color::WHITE);
+    for (&hv, flips) in tile_flips.iter() {
+        if flips % 2 == 1 {
+            floor.insert(to_point(*hv), Color::BLACK);
+        }
     }
 
-    dbg!(&board.blacks());
+    let neighbors = PointyDirection::ALL.map(|d| to_point(d.offset()));
+    let mut board = Automaton::new(floor, neighbors, flip).with_margin(1);
+    board.run(100);
+
+    dbg!(board.grid().len());
+
+    if let Some(path) = std::env::args().nth(1) {
+        std::fs::write(path, to_svg(board.grid()).to_string())?;
+    }
 
     Ok(())
 }
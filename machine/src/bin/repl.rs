@@ -0,0 +1,28 @@
+use std::io;
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+use machine::Session;
+
+fn main() -> Result<()> {
+    let mut session = Session::new();
+    println!("Handheld machine REPL; :help for commands");
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    while !session.is_done() {
+        print!("> ");
+        io::stdout().flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        match session.exec(&line) {
+            Ok(out) if out.is_empty() => {}
+            Ok(out) => println!("{}", out),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    Ok(())
+}
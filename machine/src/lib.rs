@@ -2,15 +2,27 @@ use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+mod repl;
+mod scheduler;
+
+pub use repl::*;
+pub use scheduler::*;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Instruction {
     Acc(isize),
     Jmp(isize),
     Nop(isize),
+    /// Send the accumulator on the given channel.
+    Snd(isize),
+    /// Receive a value from the given channel into the accumulator, blocking if it is empty.
+    Rcv(isize),
 }
 
 impl Instruction {
-    pub fn exec(&self, m: &mut RegisterFile) {
+    /// Executes one instruction. Returns `Err(channel)` without advancing if it is a `rcv` on an
+    /// empty channel.
+    pub fn exec<C: Channels>(&self, m: &mut RegisterFile, io: &mut C) -> Result<(), isize> {
         match self {
             Instruction::Nop(_) => m.ip += 1,
             Instruction::Acc(x) => {
@@ -20,7 +32,16 @@ impl Instruction {
             Instruction::Jmp(x) => {
                 m.ip = (m.ip as isize + x) as usize;
             }
+            Instruction::Snd(ch) => {
+                io.send(*ch, m.acc);
+                m.ip += 1;
+            }
+            Instruction::Rcv(ch) => {
+                m.acc = io.recv(*ch).ok_or(*ch)?;
+                m.ip += 1;
+            }
         };
+        Ok(())
     }
 
     pub fn parse_prog(input: &str) -> Vec<Self> {
@@ -29,6 +50,21 @@ impl Instruction {
             .map(|l| l.parse().expect("Could not parse instruction"))
             .collect()
     }
+
+    /// Like `parse_prog`, but skips blank lines and returns an error naming the first line that
+    /// doesn't parse, rather than panicking.
+    pub fn try_parse_prog(input: &str) -> anyhow::Result<Vec<Self>> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| {
+                l.trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Line {}: {}", i + 1, e))
+            })
+            .collect()
+    }
 }
 
 impl Display for Instruction {
@@ -37,6 +73,8 @@ impl Display for Instruction {
             Instruction::Acc(x) => write!(f, "acc {}", x),
             Instruction::Jmp(x) => write!(f, "jmp {}", x),
             Instruction::Nop(x) => write!(f, "nop {}", x),
+            Instruction::Snd(x) => write!(f, "snd {}", x),
+            Instruction::Rcv(x) => write!(f, "rcv {}", x),
         }
     }
 }
@@ -49,11 +87,16 @@ impl FromStr for Instruction {
         let op = parts
             .next()
             .ok_or_else(|| Self::Err::msg("Missing instruction"))?;
-        let arg = parts.next().unwrap().parse()?;
+        let arg = parts
+            .next()
+            .ok_or_else(|| Self::Err::msg("Missing argument"))?
+            .parse()?;
         match op {
             "acc" => Ok(Instruction::Acc(arg)),
             "jmp" => Ok(Instruction::Jmp(arg)),
             "nop" => Ok(Instruction::Nop(arg)),
+            "snd" => Ok(Instruction::Snd(arg)),
+            "rcv" => Ok(Instruction::Rcv(arg)),
             s => Err(Self::Err::msg(format!("Bad input {}", s))),
         }
     }
@@ -71,6 +114,42 @@ impl RegisterFile {
     }
 }
 
+impl Default for RegisterFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Message queues for the `snd` and `rcv` instructions.
+pub trait Channels {
+    fn send(&mut self, channel: isize, value: isize);
+    fn recv(&mut self, channel: isize) -> Option<isize>;
+}
+
+/// Channels for a machine with nothing connected: sends are dropped and receives block forever.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Disconnected;
+
+impl Channels for Disconnected {
+    fn send(&mut self, _channel: isize, _value: isize) {}
+
+    fn recv(&mut self, _channel: isize) -> Option<isize> {
+        None
+    }
+}
+
+/// What a machine is doing after its last step.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum State {
+    Ready,
+    /// Waiting on a `rcv` from the given channel.
+    Blocked(isize),
+    /// The instruction pointer is just past the end of the program.
+    Halted,
+    /// The instruction pointer left the program anywhere else.
+    Faulted,
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Machine {
     reg: RegisterFile,
@@ -94,6 +173,30 @@ impl Machine {
         }
     }
 
+    pub fn registers(&self) -> &RegisterFile {
+        &self.reg
+    }
+
+    pub fn state(&self, prog: &[Instruction]) -> State {
+        if self.reg.ip < prog.len() {
+            State::Ready
+        } else if self.reg.ip == prog.len() {
+            State::Halted
+        } else {
+            State::Faulted
+        }
+    }
+
+    /// Executes a single instruction, returning the state afterwards.
+    pub fn step<C: Channels>(&mut self, prog: &[Instruction], io: &mut C) -> State {
+        if let Some(ins) = prog.get(self.reg.ip) {
+            if let Err(ch) = ins.exec(&mut self.reg, io) {
+                return State::Blocked(ch);
+            }
+        }
+        self.state(prog)
+    }
+
     pub fn run_debug<W: Watcher>(
         &mut self,
         prog: &[Instruction],
@@ -105,7 +208,9 @@ impl Machine {
             }
 
             watcher.log(ins, &self.reg);
-            ins.exec(&mut self.reg);
+            if ins.exec(&mut self.reg, &mut Disconnected).is_err() {
+                return Err(self.reg);
+            }
         }
 
         if self.reg.ip == prog.len() {
@@ -117,7 +222,9 @@ impl Machine {
 
     pub fn run(&mut self, prog: &[Instruction]) -> Result<RegisterFile, RegisterFile> {
         while let Some(ins) = prog.get(self.reg.ip) {
-            ins.exec(&mut self.reg);
+            if ins.exec(&mut self.reg, &mut Disconnected).is_err() {
+                return Err(self.reg);
+            }
         }
 
         if self.reg.ip == prog.len() {
@@ -128,6 +235,12 @@ impl Machine {
     }
 }
 
+impl Default for Machine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Instruction, Machine, RegisterFile, Watcher};
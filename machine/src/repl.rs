@@ -0,0 +1,262 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+
+use crate::{Instruction, Machine, State};
+
+/// Maximum number of instructions `:run` executes before giving up.
+const STEP_LIMIT: usize = 1_000_000;
+
+const HELP: &str = r#"<instruction>       append an instruction, e.g. `acc +1`
+:insert <n> <ins>   insert an instruction before line n
+:delete <n>         delete line n
+:list               show the program
+:load <file>        replace the program with a file
+:save <file>        write the program to a file
+:undo               revert the last edit
+:run                run until halted, blocked or the step limit
+:step [n]           execute n instructions (default 1)
+:regs               show the registers
+:reset              reset the registers and channels
+:send <ch> <value>  queue a value on a channel
+:drain <ch>         take every value waiting on a channel
+:help               show this text
+:quit               exit"#;
+
+/// The state behind the interactive machine REPL: a program being edited, its edit history, and a
+/// machine running it.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    prog: Vec<Instruction>,
+    history: Vec<Vec<Instruction>>,
+    machine: Machine,
+    channels: HashMap<isize, VecDeque<isize>>,
+    done: bool,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn prog(&self) -> &[Instruction] {
+        &self.prog
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    /// Whether `:quit` has been entered.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Handles one line of input, returning the text to show the user.
+    pub fn exec(&mut self, line: &str) -> Result<String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(String::new());
+        }
+        if !line.starts_with(':') {
+            let ins = line.parse()?;
+            self.edit(|prog| prog.push(ins));
+            return Ok(format!("{:4}: {}", self.prog.len() - 1, ins));
+        }
+
+        let mut parts = line.split_whitespace();
+        let cmd = parts.next().unwrap();
+        let args: Vec<&str> = parts.collect();
+        match (cmd, args.as_slice()) {
+            (":help", []) => Ok(HELP.to_string()),
+            (":quit", []) => {
+                self.done = true;
+                Ok(String::new())
+            }
+            (":list", []) => Ok(self.listing()),
+            (":insert", [n, ins @ ..]) => {
+                let n = self.line_number(n, self.prog.len() + 1)?;
+                let ins: Instruction = ins.join(" ").parse()?;
+                self.edit(|prog| prog.insert(n, ins));
+                Ok(self.listing())
+            }
+            (":delete", [n]) => {
+                let n = self.line_number(n, self.prog.len())?;
+                self.edit(|prog| {
+                    prog.remove(n);
+                });
+                Ok(self.listing())
+            }
+            (":load", [path]) => {
+                // Parse it all before editing, so a bad file leaves the program as it was.
+                let prog = Instruction::try_parse_prog(&fs::read_to_string(path)?)?;
+                self.edit(|p| *p = prog);
+                Ok(format!("Loaded {} instructions", self.prog.len()))
+            }
+            (":save", [path]) => {
+                let mut text = self.prog.iter().join("\n");
+                text.push('\n');
+                fs::write(path, text)?;
+                Ok(format!("Saved {} instructions", self.prog.len()))
+            }
+            (":undo", []) => {
+                self.prog = self
+                    .history
+                    .pop()
+                    .ok_or_else(|| anyhow!("Nothing to undo"))?;
+                Ok(self.listing())
+            }
+            (":run", []) => Ok(self.run(STEP_LIMIT)),
+            (":step", []) => Ok(self.run(1)),
+            (":step", [n]) => Ok(self.run(n.parse()?)),
+            (":regs", []) => Ok(format!("{:?}", self.machine.registers())),
+            (":reset", []) => {
+                self.machine = Machine::new();
+                self.channels.clear();
+                Ok(format!("{:?}", self.machine.registers()))
+            }
+            (":send", [ch, value]) => {
+                self.channels
+                    .entry(ch.parse()?)
+                    .or_default()
+                    .push_back(value.parse()?);
+                Ok(String::new())
+            }
+            (":drain", [ch]) => {
+                let values: Vec<isize> = self
+                    .channels
+                    .get_mut(&ch.parse()?)
+                    .map(|q| q.drain(..).collect())
+                    .unwrap_or_default();
+                Ok(values.iter().join(" "))
+            }
+            _ => Err(anyhow!("Bad command `{}`; try :help", line)),
+        }
+    }
+
+    fn edit<F: FnOnce(&mut Vec<Instruction>)>(&mut self, f: F) {
+        self.history.push(self.prog.clone());
+        f(&mut self.prog);
+    }
+
+    /// Parses a line number, which must be less than `end`.
+    fn line_number(&self, n: &str, end: usize) -> Result<usize> {
+        let n = n.parse()?;
+        if n < end {
+            Ok(n)
+        } else {
+            Err(anyhow!("No line {}", n))
+        }
+    }
+
+    fn listing(&self) -> String {
+        self.prog
+            .iter()
+            .enumerate()
+            .map(|(i, ins)| {
+                let marker = if i == self.machine.registers().ip {
+                    ">"
+                } else {
+                    " "
+                };
+                format!("{}{:4}: {}", marker, i, ins)
+            })
+            .join("\n")
+    }
+
+    fn run(&mut self, limit: usize) -> String {
+        let mut state = self.machine.state(&self.prog);
+        let mut steps = 0;
+        while steps < limit && state == State::Ready {
+            state = self.machine.step(&self.prog, &mut self.channels);
+            if let State::Blocked(_) = state {
+                break;
+            }
+            steps += 1;
+        }
+        format!(
+            "{:?} after {} steps; {:?}",
+            state,
+            steps,
+            self.machine.registers()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Instruction, Session};
+
+    #[test]
+    fn test_edit_undo() {
+        let mut s = Session::new();
+        s.exec("acc +1").unwrap();
+        s.exec("jmp +2").unwrap();
+        s.exec(":insert 1 acc +5").unwrap();
+        assert_eq!(
+            s.prog(),
+            &[
+                Instruction::Acc(1),
+                Instruction::Acc(5),
+                Instruction::Jmp(2)
+            ]
+        );
+
+        s.exec(":delete 0").unwrap();
+        s.exec(":undo").unwrap();
+        s.exec(":undo").unwrap();
+        assert_eq!(s.prog(), &[Instruction::Acc(1), Instruction::Jmp(2)]);
+
+        assert!(s.exec("bogus 1").is_err());
+        assert!(s.exec("acc").is_err());
+        assert!(s.exec(":delete 9").is_err());
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir();
+        let good = dir.join(format!("machine-repl-good-{}.txt", std::process::id()));
+        let bad = dir.join(format!("machine-repl-bad-{}.txt", std::process::id()));
+        std::fs::write(&good, "acc +1\n\njmp -1\n").unwrap();
+        std::fs::write(&bad, "acc +1\nbogus 3\n").unwrap();
+
+        let mut s = Session::new();
+        s.exec("nop +0").unwrap();
+        let err = s
+            .exec(&format!(":load {}", bad.display()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Line 2"), "{}", err);
+        assert_eq!(s.prog(), &[Instruction::Nop(0)]);
+
+        s.exec(&format!(":load {}", good.display())).unwrap();
+        assert_eq!(s.prog(), &[Instruction::Acc(1), Instruction::Jmp(-1)]);
+        s.exec(":undo").unwrap();
+        assert_eq!(s.prog(), &[Instruction::Nop(0)]);
+
+        std::fs::remove_file(good).unwrap();
+        std::fs::remove_file(bad).unwrap();
+    }
+
+    #[test]
+    fn test_run_step() {
+        let mut s = Session::new();
+        for l in &["rcv +0", "acc +2", "snd +1"] {
+            s.exec(l).unwrap();
+        }
+
+        s.exec(":run").unwrap();
+        assert_eq!(0, s.machine().registers().ip);
+
+        s.exec(":send 0 40").unwrap();
+        s.exec(":step 2").unwrap();
+        assert_eq!(42, s.machine().registers().acc);
+        assert_eq!("", s.exec(":drain 1").unwrap());
+
+        s.exec(":run").unwrap();
+        assert_eq!("42", s.exec(":drain 1").unwrap());
+        assert_eq!(3, s.machine().registers().ip);
+    }
+}
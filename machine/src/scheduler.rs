@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::{Channels, Instruction, Machine, RegisterFile, State};
+
+impl Channels for HashMap<isize, VecDeque<isize>> {
+    fn send(&mut self, channel: isize, value: isize) {
+        self.entry(channel).or_default().push_back(value);
+    }
+
+    fn recv(&mut self, channel: isize) -> Option<isize> {
+        self.get_mut(&channel).and_then(|q| q.pop_front())
+    }
+}
+
+/// How the scheduler hands out turns.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Policy {
+    /// Each machine executes one instruction per turn.
+    RoundRobin,
+    /// Each machine runs until it blocks or stops before the next gets a turn.
+    UntilBlocked,
+}
+
+/// Every live machine is blocked on an empty channel.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Deadlock {
+    /// (machine id, channel) for each blocked machine.
+    pub waiting: Vec<(usize, isize)>,
+}
+
+impl Display for Deadlock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Deadlock:")?;
+        for (id, ch) in &self.waiting {
+            write!(f, " machine {} waiting on channel {};", id, ch)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Deadlock {}
+
+#[derive(Debug, Clone)]
+struct Node {
+    machine: Machine,
+    prog: Vec<Instruction>,
+    state: State,
+}
+
+/// Runs many machines together, connected by numbered message channels.
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    nodes: Vec<Node>,
+    channels: HashMap<isize, VecDeque<isize>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a machine running `prog`, returning its id.
+    pub fn spawn(&mut self, prog: Vec<Instruction>) -> usize {
+        let machine = Machine::new();
+        let state = machine.state(&prog);
+        self.nodes.push(Node {
+            machine,
+            prog,
+            state,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Queues a value on a channel from outside the network.
+    pub fn send(&mut self, channel: isize, value: isize) {
+        self.channels.send(channel, value);
+    }
+
+    /// Takes every value waiting on a channel.
+    pub fn drain(&mut self, channel: isize) -> Vec<isize> {
+        self.channels
+            .get_mut(&channel)
+            .map(|q| q.drain(..).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn registers(&self, id: usize) -> &RegisterFile {
+        self.nodes[id].machine.registers()
+    }
+
+    pub fn state(&self, id: usize) -> State {
+        self.nodes[id].state
+    }
+
+    /// Machines that are blocked, with the channel each is waiting on.
+    pub fn waiting(&self) -> Vec<(usize, isize)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(id, n)| match n.state {
+                State::Blocked(ch) => Some((id, ch)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Steps one machine, returning whether it executed an instruction.
+    fn step_node(&mut self, id: usize) -> bool {
+        let node = &mut self.nodes[id];
+        node.state = node.machine.step(&node.prog, &mut self.channels);
+        !matches!(node.state, State::Blocked(_))
+    }
+
+    fn is_live(&self, id: usize) -> bool {
+        matches!(self.nodes[id].state, State::Ready | State::Blocked(_))
+    }
+
+    /// Runs one machine until it blocks, halts or faults.
+    pub fn run_until_blocked(&mut self, id: usize) -> State {
+        while self.is_live(id) && self.step_node(id) {}
+        self.nodes[id].state
+    }
+
+    /// Gives every live machine one turn. Returns whether any of them made progress.
+    pub fn round(&mut self, policy: Policy) -> bool {
+        let mut progressed = false;
+        for id in 0..self.nodes.len() {
+            if !self.is_live(id) {
+                continue;
+            }
+            match policy {
+                Policy::RoundRobin => progressed |= self.step_node(id),
+                Policy::UntilBlocked => {
+                    while self.is_live(id) && self.step_node(id) {
+                        progressed = true;
+                    }
+                }
+            }
+        }
+        progressed
+    }
+
+    /// Runs until every machine has halted or faulted. Fails if the live machines are all
+    /// blocked with nothing left to receive.
+    pub fn run(&mut self, policy: Policy) -> Result<(), Deadlock> {
+        while (0..self.nodes.len()).any(|id| self.is_live(id)) {
+            if !self.round(policy) {
+                return Err(Deadlock {
+                    waiting: self.waiting(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Deadlock, Instruction, Policy, Scheduler, State};
+
+    #[test]
+    fn test_pipeline() {
+        // Each stage adds one to what it receives and passes it on.
+        let stage = |input: isize, output: isize| {
+            Instruction::parse_prog(&format!("rcv {}\nacc +1\nsnd {}", input, output))
+        };
+
+        for &policy in &[Policy::RoundRobin, Policy::UntilBlocked] {
+            let mut s = Scheduler::new();
+            s.spawn(stage(1, 2));
+            s.spawn(stage(0, 1));
+            s.send(0, 40);
+            assert_eq!(Ok(()), s.run(policy));
+            assert_eq!(vec![42], s.drain(2));
+            assert_eq!(State::Halted, s.state(0));
+        }
+    }
+
+    #[test]
+    fn test_deadlock() {
+        let mut s = Scheduler::new();
+        s.spawn(Instruction::parse_prog("rcv +1\nsnd +0"));
+        s.spawn(Instruction::parse_prog("rcv +0\nsnd +1"));
+        s.spawn(Instruction::parse_prog("acc +3"));
+
+        assert_eq!(
+            Err(Deadlock {
+                waiting: vec![(0, 1), (1, 0)]
+            }),
+            s.run(Policy::RoundRobin)
+        );
+        assert_eq!(State::Halted, s.state(2));
+        assert_eq!(3, s.registers(2).acc);
+    }
+}
@@ -0,0 +1,66 @@
+//! Serial against parallel stepping of the waiting area seating rules, on a grid the size of the
+//! day 11 input and on larger ones. Run with `cargo bench -p two_space --features parallel`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use two_space::{Automaton, DenseStore, Neighborhood};
+
+fn seating(seat: &char, neighbors: &mut dyn Iterator<Item = &char>) -> char {
+    let filled = neighbors.filter(|&&c| c == '#').count();
+    match seat {
+        'L' if filled == 0 => '#',
+        '#' if filled >= 4 => 'L',
+        &c => c,
+    }
+}
+
+/// A waiting area with roughly one floor tile in eight, the same every run.
+fn waiting_area(width: usize, height: usize) -> DenseStore<char> {
+    let mut state: u32 = 0x2020_1211;
+    let rows: Vec<Vec<char>> = (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    if state & 7 == 0 {
+                        '.'
+                    } else {
+                        'L'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    DenseStore::new(&rows)
+}
+
+fn stepping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seating");
+    for &(width, height) in &[(98, 91), (500, 500), (2000, 2000)] {
+        let grid = waiting_area(width, height);
+        let size = format!("{}x{}", width, height);
+
+        group.bench_with_input(BenchmarkId::new("serial", &size), &grid, |b, grid| {
+            b.iter(|| {
+                let mut a = Automaton::new(grid.clone(), Neighborhood::King, seating);
+                a.run(10);
+                a.into_grid()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", &size), &grid, |b, grid| {
+            b.iter(|| {
+                let mut a = Automaton::new(grid.clone(), Neighborhood::King, seating);
+                for _ in 0..10 {
+                    a.par_step();
+                }
+                a.into_grid()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, stepping);
+criterion_main!(benches);
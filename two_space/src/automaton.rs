@@ -0,0 +1,447 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+#[cfg(feature = "parallel")]
+use crate::DenseStore;
+use crate::{Grid, Neighborhood, Point, Snapshot, Visibility};
+
+/// The cells an automaton rule looks at around each point.
+pub trait Adjacency {
+    /// Appends the neighbours of `p` to `out`.
+    fn neighbors_of(&self, p: Point, out: &mut Vec<Point>);
+
+    /// Appends the cells that count `p` as a neighbour, which are those that may change when `p`
+    /// does. For symmetric adjacencies, where q is a neighbour of p whenever p is of q, these
+    /// are just the neighbours of `p`.
+    fn influenced_by(&self, p: Point, out: &mut Vec<Point>) {
+        self.neighbors_of(p, out);
+    }
+}
+
+impl Adjacency for Neighborhood {
+    fn neighbors_of(&self, p: Point, out: &mut Vec<Point>) {
+        out.extend(self.offsets().iter().map(|&o| p + o));
+    }
+}
+
+impl Adjacency for Visibility {
+    fn neighbors_of(&self, p: Point, out: &mut Vec<Point>) {
+        out.extend_from_slice(self.visible_from(p));
+    }
+
+    fn influenced_by(&self, p: Point, out: &mut Vec<Point>) {
+        out.extend_from_slice(self.visible_to(p));
+    }
+}
+
+/// Fixed offsets, such as the six directions of a hex grid in axial coordinates.
+impl<const K: usize> Adjacency for [Point; K] {
+    fn neighbors_of(&self, p: Point, out: &mut Vec<Point>) {
+        out.extend(self.iter().map(|&o| p + o));
+    }
+
+    fn influenced_by(&self, p: Point, out: &mut Vec<Point>) {
+        out.extend(self.iter().map(|&o| p - o));
+    }
+}
+
+/// Incremental steps fall back to visiting every cell when more than one in this many changed.
+const SPARSE_CHANGES: usize = 4;
+
+/// A state that the automaton returned to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    /// The first generation of the repeating sequence.
+    pub start: usize,
+    pub period: usize,
+}
+
+/// A cellular automaton over any `Grid`. Each generation applies `rule` to every cell, given the
+/// cell's glyph and an iterator over the glyphs of its neighbours. Generations are written into a
+/// second buffer, which is then swapped in, so stepping doesn't allocate a new grid.
+///
+/// Cells are evaluated over the grid's bounds grown by a margin, which defaults to 0. Give grids
+/// that cover the whole plane, like `SparseGrid`, a margin of 1 so that growth at the edges is
+/// seen.
+///
+/// In incremental mode, each step after the first only evaluates the cells that changed in the
+/// previous generation and the cells they influence, which is much faster once most of the grid
+/// has settled. It gives the same result as long as the rule depends only on a cell and its
+/// neighbours.
+pub struct Automaton<G, N, R> {
+    front: G,
+    back: G,
+    neighbors: N,
+    rule: R,
+    generation: usize,
+    margin: isize,
+    incremental: bool,
+    /// Whether `changed` holds every cell that differs between the front and back buffers.
+    tracked: bool,
+    changed: Vec<Point>,
+    counts: Vec<usize>,
+    buf: Vec<Point>,
+}
+
+impl<G, N, R> Automaton<G, N, R>
+where
+    G: Grid + Clone,
+    G::Glyph: PartialEq,
+    N: Adjacency,
+    R: FnMut(&G::Glyph, &mut dyn Iterator<Item = &G::Glyph>) -> G::Glyph,
+{
+    pub fn new(grid: G, neighbors: N, rule: R) -> Self {
+        Automaton {
+            back: grid.clone(),
+            front: grid,
+            neighbors,
+            rule,
+            generation: 0,
+            margin: 0,
+            incremental: false,
+            tracked: false,
+            changed: Vec::new(),
+            counts: Vec::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    pub fn with_margin(mut self, margin: isize) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Steps only re-evaluate cells around the last generation's changes.
+    pub fn incremental(mut self) -> Self {
+        self.incremental = true;
+        self
+    }
+
+    pub fn grid(&self) -> &G {
+        &self.front
+    }
+
+    pub fn into_grid(self) -> G {
+        self.front
+    }
+
+    /// The number of steps taken so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The number of cells that changed in each generation so far.
+    pub fn change_counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// The cells that changed in the last step, in reading order.
+    pub fn last_changed(&self) -> &[Point] {
+        &self.changed
+    }
+
+    /// Advances one generation, returning the number of cells that changed.
+    pub fn step(&mut self) -> usize {
+        let previous = mem::take(&mut self.changed);
+        // Covering the back buffer's bounds too overwrites anything stale left in it.
+        let domain = self
+            .front
+            .bounds()
+            .union(&self.back.bounds())
+            .expand(self.margin);
+        // Sorting the cells around many changes costs more than visiting every cell in order.
+        let sparse_changes = previous.len() * SPARSE_CHANGES < domain.area();
+        if self.incremental && self.tracked && sparse_changes {
+            // The back buffer already matches the front everywhere but the cells that changed,
+            // and those are re-evaluated, so skipped cells are left correct.
+            let mut candidates = Vec::with_capacity(previous.len() * 9);
+            for &p in previous.iter() {
+                candidates.push(p);
+                self.neighbors.influenced_by(p, &mut candidates);
+            }
+            candidates.sort_unstable();
+            candidates.dedup();
+            for p in candidates {
+                self.update(p);
+            }
+        } else {
+            for p in domain.points() {
+                self.update(p);
+            }
+        }
+
+        mem::swap(&mut self.front, &mut self.back);
+        self.generation += 1;
+        self.tracked = true;
+        self.counts.push(self.changed.len());
+        self.changed.len()
+    }
+
+    /// Writes the next glyph of `p` into the back buffer.
+    fn update(&mut self, p: Point) {
+        let current = match self.front.at(p) {
+            Some(g) => g,
+            None => return,
+        };
+        self.buf.clear();
+        self.neighbors.neighbors_of(p, &mut self.buf);
+        let front = &self.front;
+        let mut around = self.buf.iter().filter_map(|&q| front.at(q));
+        let next = (self.rule)(current, &mut around);
+        if &next != current {
+            self.changed.push(p);
+        }
+        self.back.set(p, next);
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until a generation changes nothing, returning the generation reached. Never returns
+    /// if the automaton doesn't settle; see `find_cycle`.
+    pub fn run_until_stable(&mut self) -> usize {
+        while self.step() > 0 {}
+        self.generation
+    }
+}
+
+impl<G, N, R> Automaton<G, N, R>
+where
+    G: Grid + Clone,
+    G::Glyph: Clone + PartialEq + Hash,
+    N: Adjacency,
+    R: FnMut(&G::Glyph, &mut dyn Iterator<Item = &G::Glyph>) -> G::Glyph,
+{
+    fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for p in self.front.bounds().points() {
+            if let Some(g) = self.front.at(p) {
+                p.hash(&mut hasher);
+                g.hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Steps until the grid repeats an earlier state, for at most `limit` generations. A stable
+    /// grid is a cycle with period 1. States are looked up by hash, then compared cell by cell,
+    /// so a copy of every generation is kept until this returns.
+    pub fn find_cycle(&mut self, limit: usize) -> Option<Cycle> {
+        let mut seen = HashMap::<u64, Vec<(usize, Snapshot<_>)>>::new();
+        for i in 0..=limit {
+            if i > 0 {
+                self.step();
+            }
+            let snapshot = Snapshot::of(&self.front);
+            let same_hash = seen.entry(self.state_hash()).or_default();
+            if let Some(&(start, _)) = same_hash.iter().find(|(_, s)| *s == snapshot) {
+                return Some(Cycle {
+                    start,
+                    period: self.generation - start,
+                });
+            }
+            same_hash.push((self.generation, snapshot));
+        }
+        None
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<Glyph, N, R> Automaton<DenseStore<Glyph>, N, R>
+where
+    Glyph: Clone + PartialEq + Send + Sync,
+    N: Adjacency + Sync,
+    R: Fn(&Glyph, &mut dyn Iterator<Item = &Glyph>) -> Glyph + Sync,
+{
+    /// Like `step`, but computed across threads in bands of rows. Gives the same result as `step`,
+    /// so the rule must not rely on the order cells are visited in.
+    pub fn par_step(&mut self) -> usize {
+        use rayon::prelude::*;
+
+        let front = &self.front;
+        let neighbors = &self.neighbors;
+        let rule = &self.rule;
+        let (origin, width) = (front.origin(), front.width());
+        let rows = crate::parallel::band_rows(front.height());
+
+        let changed = self
+            .back
+            .par_row_bands_mut(rows)
+            .map_init(Vec::new, |buf, (y0, band)| {
+                let mut changed = 0;
+                for (i, cell) in band.iter_mut().enumerate() {
+                    let p = origin + Point::new((i % width) as isize, y0 + (i / width) as isize);
+                    let current = &front[p];
+                    buf.clear();
+                    neighbors.neighbors_of(p, buf);
+                    let mut around = buf.iter().filter_map(|&q| front.at(q));
+                    *cell = rule(current, &mut around);
+                    if cell != current {
+                        changed += 1;
+                    }
+                }
+                changed
+            })
+            .sum();
+
+        mem::swap(&mut self.front, &mut self.back);
+        self.generation += 1;
+        // Which cells changed isn't kept, so an incremental step after this one looks at them all.
+        self.tracked = false;
+        self.changed.clear();
+        self.counts.push(changed);
+        changed
+    }
+
+    /// Like `run_until_stable`, stepping with `par_step`.
+    pub fn par_run_until_stable(&mut self) -> usize {
+        while self.par_step() > 0 {}
+        self.generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Automaton, Cycle, DenseStore, Neighborhood, Point, SparseGrid, Visibility};
+
+    fn life(cell: &bool, neighbors: &mut dyn Iterator<Item = &bool>) -> bool {
+        let alive = neighbors.filter(|&&n| n).count();
+        alive == 3 || (*cell && alive == 2)
+    }
+
+    fn parse(s: &str) -> DenseStore<bool> {
+        let rows: Vec<Vec<bool>> = s
+            .lines()
+            .map(|l| l.chars().map(|c| c == '#').collect())
+            .collect();
+        DenseStore::new(&rows)
+    }
+
+    #[test]
+    fn blinker_test() {
+        let grid = parse(".....\n..#..\n..#..\n..#..\n.....");
+        let mut a = Automaton::new(grid.clone(), Neighborhood::King, life);
+        assert_eq!(a.step(), 4);
+        assert_eq!(a.grid(), &parse(".....\n.....\n.###.\n.....\n....."));
+        assert_eq!(a.generation(), 1);
+        assert_eq!(
+            a.last_changed(),
+            &[
+                Point::new(2, 1),
+                Point::new(1, 2),
+                Point::new(3, 2),
+                Point::new(2, 3)
+            ]
+        );
+
+        let mut a = Automaton::new(grid, Neighborhood::King, life);
+        assert_eq!(
+            a.find_cycle(10),
+            Some(Cycle {
+                start: 0,
+                period: 2
+            })
+        );
+    }
+
+    #[test]
+    fn stable_test() {
+        let block = parse("....\n.##.\n.#..\n....");
+        let mut a = Automaton::new(block, Neighborhood::King, life);
+        assert_eq!(a.run_until_stable(), 2);
+        assert_eq!(a.grid(), &parse("....\n.##.\n.##.\n...."));
+        assert_eq!(a.change_counts(), &[1, 0]);
+    }
+
+    #[test]
+    fn incremental_test() {
+        let mut rows = vec![vec![false; 30]; 20];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (i * 13 + j * 7) % 5 < 2;
+            }
+        }
+        let grid = DenseStore::new(&rows);
+        let mut full = Automaton::new(grid.clone(), Neighborhood::King, life);
+        let mut incremental = Automaton::new(grid, Neighborhood::King, life).incremental();
+        for _ in 0..30 {
+            assert_eq!(incremental.step(), full.step());
+            assert_eq!(incremental.grid(), full.grid());
+            assert_eq!(incremental.last_changed(), full.last_changed());
+        }
+
+        // Cells outside the old bounds are reached through the neighbours of the changes.
+        let glider = parse(".#.\n..#\n###");
+        let grid = SparseGrid::from_dense(&glider, false);
+        let mut a = Automaton::new(grid, Neighborhood::King, life)
+            .with_margin(1)
+            .incremental();
+        a.run(8);
+        assert_eq!(a.grid().to_dense(), glider.with_origin(Point::new(2, 2)));
+        assert!(a.change_counts().iter().all(|&n| n > 0));
+
+        // One-way offsets are followed backwards to find who sees a change.
+        let east = [Point::new(1, 0)];
+        let spread = |cell: &bool, n: &mut dyn Iterator<Item = &bool>| {
+            *cell || n.filter(|&&b| b).count() > 0
+        };
+        let mut line = Automaton::new(parse("...#.."), east, spread).incremental();
+        assert_eq!(line.run_until_stable(), 4);
+        assert_eq!(line.grid(), &parse("####.."));
+        assert_eq!(line.change_counts(), &[1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn visibility_test() {
+        // The floor notes whether it can see a filled seat, though no seat sees the floor.
+        let grid: DenseStore<char> = ".....\n.....\n..L..\n.....\n.....".parse().unwrap();
+        let seats = Visibility::new(&grid, Neighborhood::King, |&c| c == 'L' || c == '#');
+        let rule = |c: &char, n: &mut dyn Iterator<Item = &char>| match c {
+            'L' => '#',
+            '#' => 'L',
+            _ if n.filter(|&&s| s == '#').count() > 0 => '*',
+            _ => '.',
+        };
+        let mut full = Automaton::new(grid.clone(), seats.clone(), rule);
+        let mut incremental = Automaton::new(grid, seats, rule).incremental();
+        for _ in 0..4 {
+            assert_eq!(incremental.step(), full.step());
+            assert_eq!(incremental.grid(), full.grid());
+        }
+        assert_eq!(full.change_counts(), &[1, 17, 17, 17]);
+    }
+
+    #[test]
+    fn sparse_glider_test() {
+        let glider = parse(".#.\n..#\n###");
+        let grid = SparseGrid::from_dense(&glider, false);
+        let mut a = Automaton::new(grid, Neighborhood::King, life).with_margin(1);
+        a.run(4);
+        assert_eq!(a.grid().len(), 5);
+        assert_eq!(a.grid().to_dense(), glider.with_origin(Point::new(1, 1)));
+        assert_eq!(a.find_cycle(8), None);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_step_test() {
+        let mut rows = vec![vec![false; 40]; 50];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (i * 31 + j * 17) % 7 < 3;
+            }
+        }
+        let grid = DenseStore::new(&rows);
+        let mut serial = Automaton::new(grid.clone(), Neighborhood::King, life);
+        let mut parallel = Automaton::new(grid, Neighborhood::King, life);
+        for _ in 0..20 {
+            assert_eq!(parallel.par_step(), serial.step());
+            assert_eq!(parallel.grid(), serial.grid());
+        }
+    }
+}
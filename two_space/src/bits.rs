@@ -0,0 +1,418 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::{DenseStore, Grid, Neighborhood, Point, Rect};
+
+const WORD: usize = 64;
+
+/// A fixed size grid of booleans, packed 64 to a word with each row starting on a new word. Cell
+/// `(x, y)` is bit `x % 64` of word `x / 64` of row `y`. Bits past the end of a row are always
+/// clear, so whole words can be counted and compared directly.
+///
+/// Suits two-state automata, where a generation is a handful of shifts and boolean operations
+/// over the whole grid rather than a rule applied cell by cell.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BitGrid {
+    data: Vec<u64>,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl BitGrid {
+    /// A grid with every cell clear.
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(WORD);
+        BitGrid {
+            data: vec![0; stride * height],
+            width,
+            height,
+            stride,
+        }
+    }
+
+    /// Sets the cells of any grid whose glyph matches `pred`. The grid's top left corner becomes
+    /// the origin.
+    pub fn from_grid<G, F>(grid: &G, mut pred: F) -> Self
+    where
+        G: Grid,
+        F: FnMut(&G::Glyph) -> bool,
+    {
+        let bounds = grid.bounds();
+        let mut ret = BitGrid::new(bounds.width(), bounds.height());
+        for p in bounds.points() {
+            if grid.at(p).is_some_and(&mut pred) {
+                ret.set(p - bounds.min, true);
+            }
+        }
+        ret
+    }
+
+    pub fn to_dense(&self) -> DenseStore<bool> {
+        let rows: Vec<Vec<bool>> = (0..self.height as isize)
+            .map(|y| {
+                (0..self.width as isize)
+                    .map(|x| self.get(Point::new(x, y)))
+                    .collect()
+            })
+            .collect();
+        DenseStore::new(&rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::with_size(Point::default(), self.width, self.height)
+    }
+
+    fn locate(&self, p: Point) -> Option<(usize, u64)> {
+        if self.bounds().contains(p) {
+            let (x, y) = (p.x as usize, p.y as usize);
+            Some((y * self.stride + x / WORD, 1 << (x % WORD)))
+        } else {
+            None
+        }
+    }
+
+    /// Whether the cell is set. Cells outside the grid are clear.
+    pub fn get(&self, p: Point) -> bool {
+        self.locate(p)
+            .is_some_and(|(i, bit)| self.data[i] & bit != 0)
+    }
+
+    /// Sets or clears a cell, returning false if `p` is outside the grid.
+    pub fn set(&mut self, p: Point, value: bool) -> bool {
+        match self.locate(p) {
+            Some((i, bit)) => {
+                if value {
+                    self.data[i] |= bit;
+                } else {
+                    self.data[i] &= !bit;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Flips a cell, returning its new value, or `None` if `p` is outside the grid.
+    pub fn toggle(&mut self, p: Point) -> Option<bool> {
+        let (i, bit) = self.locate(p)?;
+        self.data[i] ^= bit;
+        Some(self.data[i] & bit != 0)
+    }
+
+    /// The number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.data.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_clear(&self) -> bool {
+        self.data.iter().all(|&w| w == 0)
+    }
+
+    /// The set cells in reading order.
+    pub fn ones(&self) -> impl Iterator<Item = Point> + '_ {
+        self.data.iter().enumerate().flat_map(move |(i, &w)| {
+            let y = (i / self.stride) as isize;
+            let x0 = (i % self.stride) * WORD;
+            let mut w = w;
+            std::iter::from_fn(move || {
+                if w == 0 {
+                    None
+                } else {
+                    let bit = w.trailing_zeros() as usize;
+                    w &= w - 1;
+                    Some(Point::new((x0 + bit) as isize, y))
+                }
+            })
+        })
+    }
+
+    /// The words of row `y`, lowest x first.
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        &self.data[y * self.stride..(y + 1) * self.stride]
+    }
+
+    /// The bits in use in the last word of each row.
+    fn tail_mask(&self) -> u64 {
+        match self.width % WORD {
+            0 => !0,
+            r => (1 << r) - 1,
+        }
+    }
+
+    /// Clears the unused bits at the end of each row.
+    fn trim(&mut self) {
+        if self.stride == 0 {
+            return;
+        }
+        let mask = self.tail_mask();
+        for row in self.data.chunks_mut(self.stride) {
+            row[row.len() - 1] &= mask;
+        }
+    }
+
+    /// A copy with every cell moved by `d`, so the result at `p` is this grid at `p - d`. Cells
+    /// moved past an edge are lost and those uncovered are clear.
+    pub fn shifted(&self, d: Point) -> Self {
+        let mut ret = BitGrid::new(self.width, self.height);
+        let height = self.height as isize;
+        for y in 0..height {
+            let src_y = y - d.y;
+            if src_y < 0 || src_y >= height {
+                continue;
+            }
+            let src = self.row_words(src_y as usize);
+            let start = y as usize * self.stride;
+            shift_row(src, &mut ret.data[start..start + self.stride], d.x);
+        }
+        ret.trim();
+        ret
+    }
+
+    /// For every cell, how many of its neighbours are set, counted a whole word at a time.
+    pub fn neighbor_counts(&self, n: Neighborhood) -> NeighborCounts {
+        let mut planes = [
+            BitGrid::new(self.width, self.height),
+            BitGrid::new(self.width, self.height),
+            BitGrid::new(self.width, self.height),
+            BitGrid::new(self.width, self.height),
+        ];
+        for &o in n.offsets() {
+            // The neighbour at `p + o` lands on `p` when shifted by `-o`.
+            let shifted = self.shifted(-o);
+            for (i, &w) in shifted.data.iter().enumerate() {
+                // Ripple-carry add of one bit into a four bit counter per cell.
+                let mut carry = w;
+                for plane in planes.iter_mut() {
+                    let next = plane.data[i] & carry;
+                    plane.data[i] ^= carry;
+                    carry = next;
+                }
+            }
+        }
+        NeighborCounts { planes }
+    }
+
+    fn zip_with<F: Fn(u64, u64) -> u64>(&mut self, other: &BitGrid, f: F) {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "Mismatched grid sizes"
+        );
+        for (a, &b) in self.data.iter_mut().zip(other.data.iter()) {
+            *a = f(*a, b);
+        }
+    }
+}
+
+/// Moves the bits of `src` by `dx` into `dst`, which has the same length.
+fn shift_row(src: &[u64], dst: &mut [u64], dx: isize) {
+    let len = src.len() as isize;
+    let words = dx.div_euclid(WORD as isize);
+    let bits = dx.rem_euclid(WORD as isize) as u32;
+    let word = |i: isize| {
+        if i >= 0 && i < len {
+            src[i as usize]
+        } else {
+            0
+        }
+    };
+    for (i, out) in dst.iter_mut().enumerate() {
+        let i = i as isize - words;
+        *out = if bits == 0 {
+            word(i)
+        } else {
+            (word(i) << bits) | (word(i - 1) >> (WORD as u32 - bits))
+        };
+    }
+}
+
+/// Per cell neighbour counts from `BitGrid::neighbor_counts`, stored as four bit planes.
+#[derive(Debug, Clone)]
+pub struct NeighborCounts {
+    planes: [BitGrid; 4],
+}
+
+impl NeighborCounts {
+    /// The cells with exactly `k` neighbours set.
+    pub fn equals(&self, k: usize) -> BitGrid {
+        let mut ret = !&BitGrid::new(self.planes[0].width, self.planes[0].height);
+        if k >= 1 << self.planes.len() {
+            return !&ret;
+        }
+        for (bit, plane) in self.planes.iter().enumerate() {
+            if k & (1 << bit) != 0 {
+                ret &= plane;
+            } else {
+                ret &= &!plane;
+            }
+        }
+        ret
+    }
+
+    /// The cells with at least `k` neighbours set.
+    pub fn at_least(&self, k: usize) -> BitGrid {
+        (k..=8).fold(
+            BitGrid::new(self.planes[0].width, self.planes[0].height),
+            |acc, i| &acc | &self.equals(i),
+        )
+    }
+
+    /// The count for one cell.
+    pub fn get(&self, p: Point) -> usize {
+        self.planes
+            .iter()
+            .enumerate()
+            .filter(|(_, plane)| plane.get(p))
+            .map(|(bit, _)| 1 << bit)
+            .sum()
+    }
+}
+
+impl From<&DenseStore<bool>> for BitGrid {
+    fn from(store: &DenseStore<bool>) -> Self {
+        BitGrid::from_grid(store, |&b| b)
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut ret = self.clone();
+        for w in ret.data.iter_mut() {
+            *w = !*w;
+        }
+        ret.trim();
+        ret
+    }
+}
+
+macro_rules! bit_op {
+    ($op:ident, $fn:ident, $assign:ident, $assign_fn:ident, $e:tt) => {
+        impl $assign<&BitGrid> for BitGrid {
+            fn $assign_fn(&mut self, rhs: &BitGrid) {
+                self.zip_with(rhs, |a, b| a $e b);
+            }
+        }
+
+        impl $op for &BitGrid {
+            type Output = BitGrid;
+
+            fn $fn(self, rhs: &BitGrid) -> BitGrid {
+                let mut ret = self.clone();
+                ret.zip_with(rhs, |a, b| a $e b);
+                ret
+            }
+        }
+    };
+}
+
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for y in 0..self.height as isize {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width as isize {
+                write!(f, "{}", if self.get(Point::new(x, y)) { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Automaton, BitGrid, DenseStore, Neighborhood, Point};
+
+    fn parse(s: &str) -> BitGrid {
+        let rows: Vec<Vec<bool>> = s
+            .lines()
+            .map(|l| l.chars().map(|c| c == '#').collect())
+            .collect();
+        BitGrid::from(&DenseStore::new(&rows))
+    }
+
+    #[test]
+    fn bits_test() {
+        let mut grid = BitGrid::new(70, 3);
+        assert!(grid.set(Point::new(69, 2), true));
+        assert!(grid.set(Point::new(63, 0), true));
+        assert!(!grid.set(Point::new(70, 0), true));
+        assert_eq!(grid.toggle(Point::new(0, 1)), Some(true));
+        assert_eq!(grid.count_ones(), 3);
+        assert_eq!(
+            grid.ones().collect::<Vec<_>>(),
+            vec![Point::new(63, 0), Point::new(0, 1), Point::new(69, 2)]
+        );
+
+        let moved = grid.shifted(Point::new(1, 1));
+        assert_eq!(
+            moved.ones().collect::<Vec<_>>(),
+            vec![Point::new(64, 1), Point::new(1, 2)]
+        );
+        assert_eq!(
+            moved
+                .shifted(Point::new(-64, -1))
+                .ones()
+                .collect::<Vec<_>>(),
+            vec![Point::new(0, 0)]
+        );
+        assert_eq!(grid.shifted(Point::new(0, 3)).count_ones(), 0);
+
+        let inverse = !&grid;
+        assert_eq!(inverse.count_ones(), 70 * 3 - 3);
+        assert!((&inverse & &grid).is_clear());
+        assert_eq!((&inverse ^ &grid).count_ones(), 210);
+        assert_eq!(BitGrid::from(&grid.to_dense()), grid);
+    }
+
+    #[test]
+    fn life_test() {
+        let glider = parse(".#....\n..#...\n###...\n......\n......");
+        let step = |g: &BitGrid| {
+            let counts = g.neighbor_counts(Neighborhood::King);
+            &counts.equals(3) | &(g & &counts.equals(2))
+        };
+        assert_eq!(
+            glider
+                .neighbor_counts(Neighborhood::King)
+                .get(Point::new(1, 1)),
+            5
+        );
+        let four = (0..4).fold(glider.clone(), |g, _| step(&g));
+        assert_eq!(four, glider.shifted(Point::new(1, 1)));
+        assert_eq!(four.to_string(), "......\n..#...\n...#..\n.###..\n......");
+    }
+
+    #[test]
+    fn matches_automaton_test() {
+        let rows: Vec<Vec<bool>> = (0..12)
+            .map(|y| (0..130).map(|x| (x * 13 + y * 7) % 5 < 2).collect())
+            .collect();
+        let life = |&c: &bool, n: &mut dyn Iterator<Item = &bool>| {
+            let alive = n.filter(|&&b| b).count();
+            alive == 3 || (c && alive == 2)
+        };
+        let mut automaton = Automaton::new(DenseStore::new(&rows), Neighborhood::King, life);
+        let mut bits = BitGrid::from(&DenseStore::new(&rows));
+        for _ in 0..6 {
+            automaton.step();
+            let counts = bits.neighbor_counts(Neighborhood::King);
+            bits = &counts.equals(3) | &(&bits & &counts.equals(2));
+            assert_eq!(&bits.to_dense(), automaton.grid());
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{Grid, Point};
+
+/// A cell that differs between two grids. `None` means the cell isn't part of that grid.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Change<Glyph> {
+    pub point: Point,
+    pub from: Option<Glyph>,
+    pub to: Option<Glyph>,
+}
+
+/// Every cell that differs between `before` and `after`, in reading order. The grids may be of
+/// different types and sizes; cells are compared over both their bounds.
+pub fn diff<A, B>(before: &A, after: &B) -> Vec<Change<A::Glyph>>
+where
+    A: Grid,
+    B: Grid<Glyph = A::Glyph>,
+    A::Glyph: Clone + PartialEq,
+{
+    before
+        .bounds()
+        .union(&after.bounds())
+        .points()
+        .filter_map(|p| {
+            let (from, to) = (before.at(p), after.at(p));
+            if from == to {
+                None
+            } else {
+                Some(Change {
+                    point: p,
+                    from: from.cloned(),
+                    to: to.cloned(),
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diff, Change, DenseStore, Point, SparseGrid};
+
+    #[test]
+    fn diff_test() {
+        let before: DenseStore<char> = "#.\n.#".parse().unwrap();
+        let after: DenseStore<char> = "##\n.#".parse().unwrap();
+        assert_eq!(
+            diff(&before, &after),
+            vec![Change {
+                point: Point::new(1, 0),
+                from: Some('.'),
+                to: Some('#')
+            }]
+        );
+        assert!(diff(&before, &before.clone()).is_empty());
+
+        // A grid placed one row up gains a row and loses one.
+        let moved = before.clone().with_origin(Point::new(0, -1));
+        let changes = diff(&before, &moved);
+        assert_eq!(changes.len(), 6);
+        assert_eq!(changes[0].from, None);
+        assert_eq!(changes[5].to, None);
+
+        let mut sparse = SparseGrid::from_dense(&before, '.');
+        sparse.insert(Point::new(3, 3), '#');
+        let changes = diff(&SparseGrid::from_dense(&before, '.'), &sparse);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].point, Point::new(3, 3));
+    }
+}
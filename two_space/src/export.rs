@@ -0,0 +1,207 @@
+#[cfg(feature = "export")]
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Write};
+
+use crate::{Grid, Point};
+
+/// A colour, 8 bits per channel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+}
+
+/// As a CSS hex colour, like `#ff8000`.
+impl Display for Rgb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// An RGB raster, stored as 3 bytes per pixel in reading order.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        let Rgb(r, g, b) = background;
+        Image {
+            width,
+            height,
+            pixels: [r, g, b].repeat(width * height),
+        }
+    }
+
+    /// Draws each cell of a grid as a `scale` by `scale` square, coloured by `palette`. Cells
+    /// missing from the grid are left black.
+    pub fn render<G, F>(grid: &G, scale: usize, mut palette: F) -> Self
+    where
+        G: Grid,
+        F: FnMut(&G::Glyph) -> Rgb,
+    {
+        let bounds = grid.bounds();
+        let mut image = Image::new(bounds.width() * scale, bounds.height() * scale, Rgb::BLACK);
+        for p in bounds.points() {
+            if let Some(g) = grid.at(p) {
+                let colour = palette(g);
+                let Point { x, y } = p - bounds.min;
+                let (x, y) = (x as usize * scale, y as usize * scale);
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        image.put(x + dx, y + dy, colour);
+                    }
+                }
+            }
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The raw RGB bytes.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        let i = (y * self.width + x) * 3;
+        Rgb(self.pixels[i], self.pixels[i + 1], self.pixels[i + 2])
+    }
+
+    pub fn put(&mut self, x: usize, y: usize, colour: Rgb) {
+        let i = (y * self.width + x) * 3;
+        self.pixels[i..i + 3].copy_from_slice(&[colour.0, colour.1, colour.2]);
+    }
+
+    /// Writes a binary PPM (P6), which most image viewers open and needs no encoder.
+    pub fn write_ppm<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(&self.pixels)
+    }
+
+    #[cfg(feature = "export")]
+    pub fn write_png<W: Write>(&self, w: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(w, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+}
+
+/// Writes a sequence of equally sized images as a looping animated GIF, such as the generations
+/// of an automaton. The animation is finished when the recorder is dropped.
+#[cfg(feature = "export")]
+pub struct GifRecorder<W: Write> {
+    encoder: gif::Encoder<W>,
+    width: u16,
+    height: u16,
+    delay: u16,
+}
+
+#[cfg(feature = "export")]
+impl<W: Write> GifRecorder<W> {
+    /// Starts an animation whose frames show for `delay` hundredths of a second each.
+    pub fn new(w: W, width: usize, height: usize, delay: u16) -> io::Result<Self> {
+        let too_large = |_| io::Error::new(io::ErrorKind::InvalidInput, "Image too large for GIF");
+        let width = u16::try_from(width).map_err(too_large)?;
+        let height = u16::try_from(height).map_err(too_large)?;
+        let mut encoder = gif::Encoder::new(w, width, height, &[]).map_err(gif_error)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(gif_error)?;
+        Ok(GifRecorder {
+            encoder,
+            width,
+            height,
+            delay,
+        })
+    }
+
+    pub fn frame(&mut self, image: &Image) -> io::Result<()> {
+        if image.width() != self.width as usize || image.height() != self.height as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Frame size differs from the animation",
+            ));
+        }
+        let mut frame = gif::Frame::from_rgb_speed(self.width, self.height, image.pixels(), 10);
+        frame.delay = self.delay;
+        self.encoder.write_frame(&frame).map_err(gif_error)
+    }
+
+    /// Finishes the animation and returns the writer.
+    pub fn finish(self) -> io::Result<W> {
+        self.encoder.into_inner()
+    }
+}
+
+#[cfg(feature = "export")]
+fn gif_error(e: gif::EncodingError) -> io::Error {
+    match e {
+        gif::EncodingError::Io(e) => e,
+        e => io::Error::new(io::ErrorKind::InvalidData, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DenseStore, Image, Rgb};
+
+    fn palette(c: &char) -> Rgb {
+        if *c == '#' {
+            Rgb::WHITE
+        } else {
+            Rgb(0, 0, 128)
+        }
+    }
+
+    #[test]
+    fn ppm_test() {
+        let grid: DenseStore<char> = "#.\n.#".parse().unwrap();
+        let image = Image::render(&grid, 2, palette);
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.get(1, 1), Rgb::WHITE);
+        assert_eq!(image.get(2, 1), Rgb(0, 0, 128));
+        assert_eq!(image.get(3, 3), Rgb::WHITE);
+
+        let mut out = Vec::new();
+        image.write_ppm(&mut out).unwrap();
+        assert!(out.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(out.len(), 11 + 4 * 4 * 3);
+    }
+
+    #[cfg(feature = "export")]
+    #[test]
+    fn encode_test() {
+        use crate::GifRecorder;
+
+        let grid: DenseStore<char> = "#.\n.#".parse().unwrap();
+        let image = Image::render(&grid, 3, palette);
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let mut gif = GifRecorder::new(Vec::new(), 6, 6, 5).unwrap();
+        gif.frame(&image).unwrap();
+        gif.frame(&Image::new(6, 6, Rgb::BLACK)).unwrap();
+        assert!(gif.frame(&Image::new(2, 2, Rgb::BLACK)).is_err());
+        let gif = gif.finish().unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+}
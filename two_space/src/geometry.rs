@@ -0,0 +1,286 @@
+use crate::Point;
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Point {
+    /// The cells of a line drawn from here to `end`, both included, by Bresenham's algorithm.
+    /// Horizontal, vertical and diagonal lines visit exactly the cells you'd expect.
+    pub fn line_to(self, end: Point) -> impl Iterator<Item = Point> {
+        let d = (end - self).abs();
+        let step = (end - self).signum();
+        let mut err = d.x - d.y;
+        let mut next = Some(self);
+        std::iter::from_fn(move || {
+            let p = next?;
+            next = if p == end {
+                None
+            } else {
+                let mut q = p;
+                let e2 = 2 * err;
+                if e2 >= -d.y {
+                    err -= d.y;
+                    q.x += step.x;
+                }
+                if e2 <= d.x {
+                    err += d.x;
+                    q.y += step.y;
+                }
+                Some(q)
+            };
+            Some(p)
+        })
+    }
+}
+
+/// A straight line between two points, both included.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment {
+    pub a: Point,
+    pub b: Point,
+}
+
+/// Where two segments meet.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Intersection {
+    At(Point),
+    /// The segments are collinear and share this stretch, which runs the same way as the first.
+    Overlap(Segment),
+}
+
+impl Segment {
+    pub fn new(a: Point, b: Point) -> Self {
+        Segment { a, b }
+    }
+
+    /// Horizontal or vertical.
+    pub fn is_axis_aligned(&self) -> bool {
+        self.a.x == self.b.x || self.a.y == self.b.y
+    }
+
+    /// At 45 degrees, but not a single point.
+    pub fn is_diagonal(&self) -> bool {
+        let d = (self.b - self.a).abs();
+        d.x == d.y && d.x != 0
+    }
+
+    /// The smallest step from `a` towards `b` that lands on lattice points, or zero if the
+    /// segment is a single point.
+    pub fn step(&self) -> Point {
+        let d = self.b - self.a;
+        match gcd(d.x, d.y) {
+            0 => d,
+            n => d / n,
+        }
+    }
+
+    /// The lattice points exactly on the segment, from `a` to `b`. Unlike `points`, a shallow
+    /// line may have only its ends.
+    pub fn lattice_points(&self) -> impl Iterator<Item = Point> {
+        let (a, step) = (self.a, self.step());
+        let n = gcd(self.b.x - a.x, self.b.y - a.y);
+        (0..=n).map(move |i| a + step * i)
+    }
+
+    /// The cells a line from `a` to `b` would be drawn through, as by `Point::line_to`.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        self.a.line_to(self.b)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        let (min, max) = (self.a.component_min(self.b), self.a.component_max(self.b));
+        (p - self.a).cross(&(self.b - self.a)) == 0
+            && min.x <= p.x
+            && p.x <= max.x
+            && min.y <= p.y
+            && p.y <= max.y
+    }
+
+    /// Whether the segments touch anywhere, even between lattice points.
+    pub fn intersects(&self, other: &Segment) -> bool {
+        let side = |s: &Segment, p: Point| (s.b - s.a).cross(&(p - s.a)).signum();
+        let (d1, d2) = (side(other, self.a), side(other, self.b));
+        let (d3, d4) = (side(self, other.a), side(self, other.b));
+        if d1 * d2 < 0 && d3 * d4 < 0 {
+            return true;
+        }
+        other.contains(self.a)
+            || other.contains(self.b)
+            || self.contains(other.a)
+            || self.contains(other.b)
+    }
+
+    /// Where the segments meet, if that is a lattice point or a shared stretch. Segments that
+    /// only cross between lattice points have no intersection here, though `intersects` is
+    /// still true of them.
+    pub fn intersection(&self, other: &Segment) -> Option<Intersection> {
+        let (d1, d2) = (self.b - self.a, other.b - other.a);
+        if d1 == Point::default() {
+            return other.contains(self.a).then_some(Intersection::At(self.a));
+        }
+        if d2 == Point::default() {
+            return self.contains(other.a).then_some(Intersection::At(other.a));
+        }
+
+        let to_other = other.a - self.a;
+        let mut denom = d1.cross(&d2);
+        if denom == 0 {
+            if to_other.cross(&d1) != 0 {
+                return None;
+            }
+            // Collinear: measure every end along this segment and keep the shared part.
+            let along = |p: Point| (p - self.a).dot(&d1);
+            let ends = [self.a, self.b, other.a, other.b];
+            let (lo, hi) = (along(other.a), along(other.b));
+            let start = 0.max(lo.min(hi));
+            let end = along(self.b).min(lo.max(hi));
+            if start > end {
+                return None;
+            }
+            let at = |t| *ends.iter().find(|&&p| along(p) == t).unwrap();
+            return Some(if start == end {
+                Intersection::At(at(start))
+            } else {
+                Intersection::Overlap(Segment::new(at(start), at(end)))
+            });
+        }
+
+        let mut t = to_other.cross(&d2);
+        let mut u = to_other.cross(&d1);
+        if denom < 0 {
+            denom = -denom;
+            t = -t;
+            u = -u;
+        }
+        if t < 0 || t > denom || u < 0 || u > denom {
+            return None;
+        }
+        let scaled = d1 * t;
+        if scaled.x % denom != 0 || scaled.y % denom != 0 {
+            return None;
+        }
+        Some(Intersection::At(self.a + scaled / denom))
+    }
+}
+
+/// Twice the area of the simple polygon with these vertices in order, by the shoelace formula.
+/// Twice so that it is always whole.
+pub fn double_area(vertices: &[Point]) -> isize {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| vertices[i].cross(&vertices[(i + 1) % n]))
+        .sum::<isize>()
+        .abs()
+}
+
+/// The number of lattice points on the edges of the polygon with these vertices in order.
+pub fn boundary_points(vertices: &[Point]) -> isize {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| {
+            let d = vertices[(i + 1) % n] - vertices[i];
+            gcd(d.x, d.y)
+        })
+        .sum()
+}
+
+/// The number of lattice points strictly inside the simple polygon with these vertices in order,
+/// by Pick's theorem.
+pub fn interior_points(vertices: &[Point]) -> isize {
+    (double_area(vertices) - boundary_points(vertices) + 2) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{boundary_points, double_area, interior_points, Intersection, Point, Segment};
+
+    fn p(x: isize, y: isize) -> Point {
+        Point::new(x, y)
+    }
+
+    #[test]
+    fn line_test() {
+        let line: Vec<Point> = p(0, 0).line_to(p(5, 2)).collect();
+        assert_eq!(
+            line,
+            vec![p(0, 0), p(1, 0), p(2, 1), p(3, 1), p(4, 2), p(5, 2)]
+        );
+        let back: Vec<Point> = p(5, 2).line_to(p(0, 0)).collect();
+        assert_eq!(back.len(), 6);
+        assert_eq!(p(3, 3).line_to(p(3, 3)).collect::<Vec<_>>(), vec![p(3, 3)]);
+        assert_eq!(
+            p(2, 0).line_to(p(0, 2)).collect::<Vec<_>>(),
+            vec![p(2, 0), p(1, 1), p(0, 2)]
+        );
+        assert_eq!(p(0, 4).line_to(p(0, -4)).count(), 9);
+    }
+
+    #[test]
+    fn segment_test() {
+        let s = Segment::new(p(0, 0), p(6, 3));
+        assert!(!s.is_axis_aligned() && !s.is_diagonal());
+        assert_eq!(s.step(), p(2, 1));
+        assert_eq!(
+            s.lattice_points().collect::<Vec<_>>(),
+            vec![p(0, 0), p(2, 1), p(4, 2), p(6, 3)]
+        );
+        assert_eq!(s.points().count(), 7);
+        assert!(s.contains(p(4, 2)));
+        assert!(!s.contains(p(3, 1)));
+        assert!(!s.contains(p(8, 4)));
+        assert!(Segment::new(p(1, 1), p(-2, -2)).is_diagonal());
+        assert!(Segment::new(p(1, 1), p(1, 9)).is_axis_aligned());
+    }
+
+    #[test]
+    fn intersection_test() {
+        let h = Segment::new(p(0, 2), p(6, 2));
+        let v = Segment::new(p(3, 0), p(3, 5));
+        assert_eq!(h.intersection(&v), Some(Intersection::At(p(3, 2))));
+
+        // These cross at (0.5, 0.5).
+        let a = Segment::new(p(0, 0), p(1, 1));
+        let b = Segment::new(p(1, 0), p(0, 1));
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b), None);
+
+        let far = Segment::new(p(10, 0), p(10, 5));
+        assert!(!h.intersects(&far));
+        assert_eq!(h.intersection(&far), None);
+
+        let along = Segment::new(p(8, 2), p(4, 2));
+        assert_eq!(
+            h.intersection(&along),
+            Some(Intersection::Overlap(Segment::new(p(4, 2), p(6, 2))))
+        );
+        let end_to_end = Segment::new(p(6, 2), p(9, 2));
+        assert_eq!(h.intersection(&end_to_end), Some(Intersection::At(p(6, 2))));
+        let parallel = Segment::new(p(0, 3), p(6, 3));
+        assert!(!h.intersects(&parallel));
+        assert_eq!(h.intersection(&parallel), None);
+
+        let dot = Segment::new(p(2, 2), p(2, 2));
+        assert_eq!(dot.intersection(&h), Some(Intersection::At(p(2, 2))));
+        assert_eq!(h.intersection(&dot), Some(Intersection::At(p(2, 2))));
+    }
+
+    #[test]
+    fn polygon_test() {
+        let square = [p(0, 0), p(4, 0), p(4, 4), p(0, 4)];
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+
+        // Winding doesn't matter.
+        let triangle = [p(0, 0), p(0, 3), p(3, 0)];
+        assert_eq!(double_area(&triangle), 9);
+        assert_eq!(boundary_points(&triangle), 9);
+        assert_eq!(interior_points(&triangle), 1);
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{DenseStore, Grid, Point, Rect};
+
+impl<Glyph: Clone> DenseStore<Glyph> {
+    /// A grid covering `bounds` with every cell set to `glyph`.
+    pub fn filled(bounds: Rect, glyph: Glyph) -> Self {
+        DenseStore {
+            data: vec![glyph; bounds.area()],
+            width: bounds.width(),
+            origin: bounds.min,
+        }
+    }
+
+    /// Changes the grid to cover exactly `bounds`. Cells inside it keep their glyphs and points,
+    /// and new cells are set to `fill`.
+    pub fn resize(&mut self, bounds: Rect, fill: Glyph) {
+        let data = bounds
+            .points()
+            .map(|p| self.get(p).cloned().unwrap_or_else(|| fill.clone()))
+            .collect();
+        *self = DenseStore {
+            data,
+            width: bounds.width(),
+            origin: bounds.min,
+        };
+    }
+
+    /// Grows the grid by `n` cells on every side, set to `fill`.
+    pub fn pad(&mut self, n: usize, fill: Glyph) {
+        self.resize(self.bounds().expand(n as isize), fill);
+    }
+}
+
+/// A dense grid over the whole plane, like `SparseGrid` but stored as a `DenseStore` for speed
+/// where most of the cells in its bounds are in use. Cells outside the bounds hold the default
+/// glyph, and writing anything else there grows the grid to include them, so points keep their
+/// meaning as it grows.
+///
+/// Puzzles that grow a little each generation can set `with_padding` so the grid grows several
+/// cells ahead of where it's written, and doesn't have to be copied every generation.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct GrowingGrid<Glyph> {
+    store: DenseStore<Glyph>,
+    default: Glyph,
+    padding: usize,
+}
+
+impl<Glyph: Clone + PartialEq> GrowingGrid<Glyph> {
+    /// An empty grid, holding only the default glyph.
+    pub fn new(default: Glyph) -> Self {
+        Self::from_dense(DenseStore::filled(Rect::empty(), default.clone()), default)
+    }
+
+    /// Starts from the cells of `store`, which stay at the same points.
+    pub fn from_dense(store: DenseStore<Glyph>, default: Glyph) -> Self {
+        GrowingGrid {
+            store,
+            default,
+            padding: 0,
+        }
+    }
+
+    /// Grows by `padding` extra cells around each write that lands outside the grid.
+    pub fn with_padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// The glyph at `p`, which is the default glyph anywhere outside the bounds.
+    pub fn get(&self, p: Point) -> &Glyph {
+        self.store.get(p).unwrap_or(&self.default)
+    }
+
+    pub fn default_glyph(&self) -> &Glyph {
+        &self.default
+    }
+
+    pub fn store(&self) -> &DenseStore<Glyph> {
+        &self.store
+    }
+
+    pub fn into_dense(self) -> DenseStore<Glyph> {
+        self.store
+    }
+
+    /// Grows the grid by `n` cells on every side now, rather than as cells are written.
+    pub fn pad(&mut self, n: usize) {
+        self.store.pad(n, self.default.clone());
+    }
+
+    /// Shrinks the grid to the smallest rectangle holding every cell that isn't the default.
+    pub fn trim(&mut self) {
+        let default = &self.default;
+        let used = crate::bounding_box(
+            self.store
+                .tiles()
+                .filter(|(g, _)| *g != default)
+                .map(|(_, p)| p),
+        );
+        self.store.resize(used, self.default.clone());
+    }
+
+    fn grow_to(&mut self, p: Point) {
+        let n = self.padding as isize;
+        let ahead = Rect::new(p - Point::new(n, n), p + Point::new(n, n));
+        let bounds = self.store.bounds().union(&ahead);
+        self.store.resize(bounds, self.default.clone());
+    }
+}
+
+impl<Glyph: Clone + PartialEq> Grid for GrowingGrid<Glyph> {
+    type Glyph = Glyph;
+
+    fn at(&self, p: Point) -> Option<&Glyph> {
+        Some(self.get(p))
+    }
+
+    /// Grows the grid to include `p` first.
+    fn at_mut(&mut self, p: Point) -> Option<&mut Glyph> {
+        if self.store.get(p).is_none() {
+            self.grow_to(p);
+        }
+        self.store.get_mut(p)
+    }
+
+    fn bounds(&self) -> Rect {
+        self.store.bounds()
+    }
+
+    /// Only grows the grid for glyphs other than the default.
+    fn set(&mut self, p: Point, g: Glyph) -> bool {
+        if let Some(cell) = self.store.get_mut(p) {
+            *cell = g;
+        } else if g != self.default {
+            self.grow_to(p);
+            self.store[p] = g;
+        }
+        true
+    }
+}
+
+impl<Glyph> Display for GrowingGrid<Glyph>
+where
+    Glyph: Clone,
+    Glyph: Into<char>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.store)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Automaton, DenseStore, Grid, GrowingGrid, Neighborhood, Point, Rect, SparseGrid};
+
+    #[test]
+    fn resize_test() {
+        let mut grid: DenseStore<char> = "ab\ncd".parse().unwrap();
+        grid.pad(1, '.');
+        assert_eq!(grid.to_string(), "....\n.ab.\n.cd.\n....");
+        assert_eq!(grid.origin(), Point::new(-1, -1));
+        assert_eq!(grid[Point::new(1, 1)], 'd');
+
+        grid.resize(Rect::new(Point::new(1, 0), Point::new(2, 3)), '_');
+        assert_eq!(grid.to_string(), "b.\nd.\n..\n__");
+        assert_eq!(grid[Point::new(1, 0)], 'b');
+
+        let filled = DenseStore::filled(Rect::with_size(Point::new(-3, 2), 2, 1), '#');
+        assert_eq!(filled.bounds().min, Point::new(-3, 2));
+        assert_eq!(filled.to_string(), "##");
+    }
+
+    #[test]
+    fn growing_test() {
+        let mut grid = GrowingGrid::new('.');
+        assert!(grid.bounds().is_empty());
+        assert_eq!(grid.at(Point::new(100, -100)), Some(&'.'));
+
+        grid.set(Point::new(2, 1), '#');
+        grid.set(Point::new(-1, -1), '#');
+        grid.set(Point::new(50, 50), '.');
+        assert_eq!(
+            grid.bounds(),
+            Rect::new(Point::new(-1, -1), Point::new(2, 1))
+        );
+        assert_eq!(grid.to_string(), "#...\n....\n...#");
+        assert_eq!(grid.get(Point::new(2, 1)), &'#');
+
+        *grid.at_mut(Point::new(0, 3)).unwrap() = '.';
+        assert_eq!(grid.bounds().max, Point::new(2, 3));
+        grid.trim();
+        assert_eq!(
+            grid.bounds(),
+            Rect::new(Point::new(-1, -1), Point::new(2, 1))
+        );
+
+        let mut padded = GrowingGrid::new('.').with_padding(2);
+        padded.set(Point::new(0, 0), '#');
+        assert_eq!(
+            padded.bounds(),
+            Rect::new(Point::new(-2, -2), Point::new(2, 2))
+        );
+        padded.set(Point::new(3, 0), '#');
+        assert_eq!(
+            padded.bounds(),
+            Rect::new(Point::new(-2, -2), Point::new(5, 2))
+        );
+    }
+
+    #[test]
+    fn automaton_test() {
+        fn life(g: &char, n: &mut dyn Iterator<Item = &char>) -> char {
+            match (g, n.filter(|&&c| c == '#').count()) {
+                (_, 3) | ('#', 2) => '#',
+                _ => '.',
+            }
+        }
+
+        let glider: DenseStore<char> = ".#.\n..#\n###".parse().unwrap();
+        let growing = GrowingGrid::from_dense(glider.clone(), '.').with_padding(4);
+        let mut dense = Automaton::new(growing, Neighborhood::King, life).with_margin(1);
+        let sparse = SparseGrid::from_dense(&glider, '.');
+        let mut sparse = Automaton::new(sparse, Neighborhood::King, life).with_margin(1);
+        dense.run(20);
+        sparse.run(20);
+
+        let mut grid = dense.into_grid();
+        grid.trim();
+        assert_eq!(grid.bounds(), sparse.grid().bounds());
+        assert_eq!(grid.bounds().min, Point::new(5, 5));
+        assert_eq!(grid.to_string(), sparse.grid().to_string());
+    }
+}
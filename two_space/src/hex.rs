@@ -0,0 +1,516 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::Point;
+
+/// A cell of a hexagonal tiling in axial coordinates. `q` counts along one axis and `r` along
+/// another at 60 degrees to it; the third cube coordinate, `s`, is implied by `q + r + s = 0`.
+///
+/// With pointy-topped hexes `q` grows to the east and `r` to the south-east; with flat-topped
+/// hexes `q` grows to the south-east and `r` to the south. See `PointyDirection` and
+/// `FlatDirection` for the unit steps of each.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+/// A hex in cube coordinates, which always sum to zero. Useful for symmetric formulas, and as
+/// the result of rounding fractional positions.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Cube {
+    pub q: isize,
+    pub r: isize,
+    pub s: isize,
+}
+
+/// Whether hexes have a point or a flat edge at the top.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Orientation {
+    Pointy,
+    Flat,
+}
+
+/// Ways of storing a hex map in a rectangular array, as `Point`s of column and row. Odd and
+/// even say which rows (`R`) or columns (`Q`) are shoved half a cell over. The row layouts are
+/// for pointy hexes and the column layouts for flat ones.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Offset {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+impl Hex {
+    pub const fn new(q: isize, r: isize) -> Self {
+        Hex { q, r }
+    }
+
+    pub fn s(&self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> Cube {
+        Cube::from(*self)
+    }
+
+    /// The neighbour in direction `d`.
+    pub fn step<D: HexDirection>(self, d: D) -> Self {
+        self + d.offset()
+    }
+
+    /// The six adjacent hexes.
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> {
+        let centre = *self;
+        PointyDirection::ALL.iter().map(move |&d| centre.step(d))
+    }
+
+    /// Steps from the origin.
+    pub fn length(&self) -> isize {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+
+    pub fn distance(&self, rhs: &Self) -> isize {
+        (*self - *rhs).length()
+    }
+
+    /// The hexes exactly `radius` steps away, clockwise from the one due west (pointy) or
+    /// north-west (flat). A ring of radius 0 is just this hex.
+    pub fn ring(&self, radius: usize) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut ret = Vec::with_capacity(6 * radius);
+        let mut h = *self + PointyDirection::W.offset() * radius as isize;
+        // Walking each side of the ring in clockwise order ends back at the start.
+        for d in PointyDirection::ALL
+            .iter()
+            .skip(5)
+            .chain(PointyDirection::ALL.iter().take(5))
+        {
+            for _ in 0..radius {
+                ret.push(h);
+                h = h.step(*d);
+            }
+        }
+        ret
+    }
+
+    /// This hex and every ring around it out to `radius`, innermost first.
+    pub fn spiral(&self, radius: usize) -> Vec<Self> {
+        (0..=radius).flat_map(|n| self.ring(n)).collect()
+    }
+
+    /// The hexes a straight line from the centre of this one to the centre of `to` passes
+    /// through, both ends included.
+    pub fn line_to(&self, to: &Self) -> Vec<Self> {
+        let n = self.distance(to);
+        if n == 0 {
+            return vec![*self];
+        }
+        let (a, b) = (self.cube(), to.cube());
+        // Nudging the ends keeps points exactly on an edge between hexes from rounding
+        // inconsistently.
+        let lerp = |a: isize, b: isize, t: f64, nudge: f64| a as f64 + (b - a) as f64 * t + nudge;
+        (0..=n)
+            .map(|i| {
+                let t = i as f64 / n as f64;
+                Cube::round(
+                    lerp(a.q, b.q, t, 1e-6),
+                    lerp(a.r, b.r, t, 2e-6),
+                    lerp(a.s, b.s, t, -3e-6),
+                )
+                .into()
+            })
+            .collect()
+    }
+
+    /// Turns this hex about `centre` by `sixths` of a full turn, clockwise as drawn with y down.
+    /// Negative amounts turn anticlockwise.
+    pub fn rotate_about(&self, centre: &Self, sixths: isize) -> Self {
+        let mut v = *self - *centre;
+        for _ in 0..sixths.rem_euclid(6) {
+            v = Hex::new(-v.r, v.q + v.r);
+        }
+        *centre + v
+    }
+
+    /// The position of the centre of the hex, with y down, for hexes whose corners are `size`
+    /// from their centres.
+    pub fn centre(&self, orientation: Orientation, size: f64) -> (f64, f64) {
+        let (q, r) = (self.q as f64, self.r as f64);
+        let root3 = 3f64.sqrt();
+        match orientation {
+            Orientation::Pointy => (size * root3 * (q + r / 2.0), size * 1.5 * r),
+            Orientation::Flat => (size * 1.5 * q, size * root3 * (r + q / 2.0)),
+        }
+    }
+
+    /// The corners of the hex, clockwise, at the same scale as `centre`.
+    pub fn corners(&self, orientation: Orientation, size: f64) -> [(f64, f64); 6] {
+        let (cx, cy) = self.centre(orientation, size);
+        let start = match orientation {
+            Orientation::Pointy => -30.0,
+            Orientation::Flat => 0.0,
+        };
+        let mut ret = [(0.0, 0.0); 6];
+        for (i, corner) in ret.iter_mut().enumerate() {
+            let angle = f64::to_radians(start + 60.0 * i as f64);
+            *corner = (cx + size * angle.cos(), cy + size * angle.sin());
+        }
+        ret
+    }
+
+    /// The column and row of this hex in an array laid out as `offset`.
+    pub fn to_offset(&self, offset: Offset) -> Point {
+        let Hex { q, r } = *self;
+        match offset {
+            Offset::OddR => Point::new(q + (r - (r & 1)) / 2, r),
+            Offset::EvenR => Point::new(q + (r + (r & 1)) / 2, r),
+            Offset::OddQ => Point::new(q, r + (q - (q & 1)) / 2),
+            Offset::EvenQ => Point::new(q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    /// The hex at a column and row of an array laid out as `offset`.
+    pub fn from_offset(p: Point, offset: Offset) -> Self {
+        let Point { x: col, y: row } = p;
+        match offset {
+            Offset::OddR => Hex::new(col - (row - (row & 1)) / 2, row),
+            Offset::EvenR => Hex::new(col - (row + (row & 1)) / 2, row),
+            Offset::OddQ => Hex::new(col, row - (col - (col & 1)) / 2),
+            Offset::EvenQ => Hex::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+}
+
+impl Cube {
+    /// The hex containing a fractional cube position.
+    pub fn round(q: f64, r: f64, s: f64) -> Self {
+        let (mut rq, mut rr, mut rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        // Rounding each alone can break the zero sum; recompute whichever moved furthest.
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        } else {
+            rs = -rq - rr;
+        }
+        Cube {
+            q: rq as isize,
+            r: rr as isize,
+            s: rs as isize,
+        }
+    }
+}
+
+impl From<Hex> for Cube {
+    fn from(h: Hex) -> Self {
+        Cube {
+            q: h.q,
+            r: h.r,
+            s: h.s(),
+        }
+    }
+}
+
+impl From<Cube> for Hex {
+    fn from(c: Cube) -> Self {
+        Hex::new(c.q, c.r)
+    }
+}
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<isize> for Hex {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Hex {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// The six ways out of a hex, for one orientation.
+pub trait HexDirection: Copy + Eq + 'static {
+    /// Clockwise, as drawn with y down.
+    const ALL: [Self; 6];
+
+    fn offset(self) -> Hex;
+
+    /// The compass abbreviation, like `ne`.
+    fn name(self) -> &'static str;
+
+    /// The direction `sixths` of a turn clockwise from this one.
+    fn turn(self, sixths: isize) -> Self {
+        let i = Self::ALL.iter().position(|&d| d == self).unwrap() as isize;
+        Self::ALL[(i + sixths).rem_euclid(6) as usize]
+    }
+
+    fn opposite(self) -> Self {
+        self.turn(3)
+    }
+}
+
+/// Directions between pointy-topped hexes, which share edges east and west.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PointyDirection {
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+    NE,
+}
+
+impl HexDirection for PointyDirection {
+    const ALL: [Self; 6] = [
+        PointyDirection::E,
+        PointyDirection::SE,
+        PointyDirection::SW,
+        PointyDirection::W,
+        PointyDirection::NW,
+        PointyDirection::NE,
+    ];
+
+    fn offset(self) -> Hex {
+        match self {
+            PointyDirection::E => Hex::new(1, 0),
+            PointyDirection::SE => Hex::new(0, 1),
+            PointyDirection::SW => Hex::new(-1, 1),
+            PointyDirection::W => Hex::new(-1, 0),
+            PointyDirection::NW => Hex::new(0, -1),
+            PointyDirection::NE => Hex::new(1, -1),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PointyDirection::E => "e",
+            PointyDirection::SE => "se",
+            PointyDirection::SW => "sw",
+            PointyDirection::W => "w",
+            PointyDirection::NW => "nw",
+            PointyDirection::NE => "ne",
+        }
+    }
+}
+
+/// Directions between flat-topped hexes, which share edges north and south.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FlatDirection {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl HexDirection for FlatDirection {
+    const ALL: [Self; 6] = [
+        FlatDirection::N,
+        FlatDirection::NE,
+        FlatDirection::SE,
+        FlatDirection::S,
+        FlatDirection::SW,
+        FlatDirection::NW,
+    ];
+
+    fn offset(self) -> Hex {
+        match self {
+            FlatDirection::N => Hex::new(0, -1),
+            FlatDirection::NE => Hex::new(1, -1),
+            FlatDirection::SE => Hex::new(1, 0),
+            FlatDirection::S => Hex::new(0, 1),
+            FlatDirection::SW => Hex::new(-1, 1),
+            FlatDirection::NW => Hex::new(-1, 0),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FlatDirection::N => "n",
+            FlatDirection::NE => "ne",
+            FlatDirection::SE => "se",
+            FlatDirection::S => "s",
+            FlatDirection::SW => "sw",
+            FlatDirection::NW => "nw",
+        }
+    }
+}
+
+/// A direction string had something other than a direction name at byte `position`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ParseHexError {
+    pub position: usize,
+}
+
+impl Display for ParseHexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Bad hex direction at {}", self.position)
+    }
+}
+
+impl Error for ParseHexError {}
+
+/// Splits an unseparated run of direction names, like `esenee`, into directions.
+pub fn parse_path<D: HexDirection>(s: &str) -> Result<Vec<D>, ParseHexError> {
+    let all = D::ALL;
+    let mut ret = Vec::new();
+    let mut rest = s;
+    while !rest.is_empty() {
+        // Longest name first, so "se" isn't read as "s" then "e".
+        let d = all
+            .iter()
+            .filter(|d| rest.starts_with(d.name()))
+            .max_by_key(|d| d.name().len())
+            .ok_or(ParseHexError {
+                position: s.len() - rest.len(),
+            })?;
+        rest = &rest[d.name().len()..];
+        ret.push(*d);
+    }
+    Ok(ret)
+}
+
+fn parse_direction<D: HexDirection>(s: &str) -> Result<D, ParseHexError> {
+    D::ALL
+        .iter()
+        .copied()
+        .find(|d| d.name() == s)
+        .ok_or(ParseHexError { position: 0 })
+}
+
+impl FromStr for PointyDirection {
+    type Err = ParseHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_direction(s)
+    }
+}
+
+impl FromStr for FlatDirection {
+    type Err = ParseHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_direction(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parse_path, Cube, FlatDirection, Hex, HexDirection, Offset, ParseHexError, Point,
+        PointyDirection,
+    };
+
+    #[test]
+    fn coords_test() {
+        let h = Hex::new(3, -5);
+        assert_eq!(h.cube(), Cube { q: 3, r: -5, s: 2 });
+        assert_eq!(Hex::from(h.cube()), h);
+        assert_eq!(h.length(), 5);
+        assert_eq!(h.distance(&Hex::new(3, -4)), 1);
+
+        for &offset in [Offset::OddR, Offset::EvenR, Offset::OddQ, Offset::EvenQ].iter() {
+            for h in Hex::default().spiral(3) {
+                assert_eq!(Hex::from_offset(h.to_offset(offset), offset), h);
+            }
+        }
+        // In odd-r, odd rows are shoved right, so south-east of (0, 0) is still column 0.
+        let se = Hex::default().step(PointyDirection::SE);
+        assert_eq!(se.to_offset(Offset::OddR), Point::new(0, 1));
+        assert_eq!(se.to_offset(Offset::EvenR), Point::new(1, 1));
+        let s = Hex::default().step(FlatDirection::S);
+        assert_eq!(s.to_offset(Offset::EvenQ), Point::new(0, 1));
+    }
+
+    #[test]
+    fn shapes_test() {
+        let centre = Hex::new(2, 1);
+        assert_eq!(centre.ring(0), vec![centre]);
+        let ring = centre.ring(2);
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|h| h.distance(&centre) == 2));
+        assert_eq!(ring[0], Hex::new(0, 1));
+        assert_eq!(ring[1], Hex::new(1, 0));
+        assert_eq!(centre.spiral(2).len(), 19);
+
+        let line = Hex::default().line_to(&Hex::new(3, -1));
+        assert_eq!(line.len(), 4);
+        assert!(line.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+        assert_eq!(line.last(), Some(&Hex::new(3, -1)));
+
+        let east = Hex::new(1, 0);
+        assert_eq!(east.rotate_about(&Hex::default(), 1), Hex::new(0, 1));
+        assert_eq!(east.rotate_about(&Hex::default(), -1), Hex::new(1, -1));
+        assert_eq!(east.rotate_about(&Hex::new(1, 1), 3), Hex::new(1, 2));
+        assert_eq!(PointyDirection::NE.turn(1), PointyDirection::E);
+        assert_eq!(FlatDirection::N.opposite(), FlatDirection::S);
+    }
+
+    #[test]
+    fn parse_test() {
+        use PointyDirection::*;
+        assert_eq!(parse_path("esenee"), Ok(vec![E, SE, NE, E]));
+        let path: Vec<FlatDirection> = parse_path("nsen").unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!(
+            parse_path::<PointyDirection>("enx"),
+            Err(ParseHexError { position: 1 })
+        );
+        assert_eq!("sw".parse(), Ok(SW));
+
+        let end = parse_path::<PointyDirection>("nwwswee")
+            .unwrap()
+            .into_iter()
+            .fold(Hex::default(), Hex::step);
+        assert_eq!(end, Hex::default());
+    }
+}
@@ -0,0 +1,133 @@
+use std::ops::RangeInclusive;
+
+/// A run of integers, `start` to `end` inclusive, like one axis of a `Rect`. An interval with
+/// `end` below `start` is empty.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Interval {
+    pub start: isize,
+    pub end: isize,
+}
+
+impl Interval {
+    pub fn new(start: isize, end: isize) -> Self {
+        Interval { start, end }
+    }
+
+    pub fn empty() -> Self {
+        Interval { start: 0, end: -1 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end < self.start
+    }
+
+    pub fn len(&self) -> usize {
+        (self.end - self.start + 1).max(0) as usize
+    }
+
+    pub fn contains(&self, n: isize) -> bool {
+        self.start <= n && n <= self.end
+    }
+
+    /// Whether every integer in `other` is also in this one. The empty interval is inside every
+    /// interval.
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The integers in both, which may be empty.
+    pub fn intersection(&self, other: &Interval) -> Self {
+        let i = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        if i.is_empty() {
+            Interval::empty()
+        } else {
+            i
+        }
+    }
+
+    /// The smallest interval containing both, including any gap between them.
+    pub fn union(&self, other: &Interval) -> Self {
+        if self.is_empty() {
+            *other
+        } else if other.is_empty() {
+            *self
+        } else {
+            Interval::new(self.start.min(other.start), self.end.max(other.end))
+        }
+    }
+
+    pub fn range(&self) -> RangeInclusive<isize> {
+        self.start..=self.end
+    }
+}
+
+impl Default for Interval {
+    fn default() -> Self {
+        Interval::empty()
+    }
+}
+
+impl From<RangeInclusive<isize>> for Interval {
+    fn from(r: RangeInclusive<isize>) -> Self {
+        Interval::new(*r.start(), *r.end())
+    }
+}
+
+/// The same integers as all of `intervals`, as disjoint intervals in increasing order. Intervals
+/// that touch end to end are joined.
+pub fn merge_intervals<I: IntoIterator<Item = Interval>>(intervals: I) -> Vec<Interval> {
+    let mut sorted: Vec<Interval> = intervals.into_iter().filter(|i| !i.is_empty()).collect();
+    sorted.sort_unstable_by_key(|i| i.start);
+    let mut merged: Vec<Interval> = Vec::with_capacity(sorted.len());
+    for i in sorted {
+        match merged.last_mut() {
+            Some(last) if i.start <= last.end + 1 => last.end = last.end.max(i.end),
+            _ => merged.push(i),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{merge_intervals, Interval};
+
+    #[test]
+    fn interval_test() {
+        let a = Interval::new(-2, 5);
+        let b: Interval = (3..=9).into();
+        assert_eq!(a.len(), 8);
+        assert!(a.contains(-2) && a.contains(5) && !a.contains(6));
+        assert_eq!(a.intersection(&b), Interval::new(3, 5));
+        assert_eq!(a.union(&b), Interval::new(-2, 9));
+        assert!(a.overlaps(&b));
+        assert!(a.union(&b).contains_interval(&b));
+        assert!(!a.contains_interval(&b));
+
+        let c = Interval::new(7, 8);
+        assert!(!a.overlaps(&c));
+        assert!(a.intersection(&c).is_empty());
+        assert_eq!(Interval::empty().union(&c), c);
+        assert_eq!(Interval::empty().len(), 0);
+        assert!(c.contains_interval(&Interval::empty()));
+    }
+
+    #[test]
+    fn merge_test() {
+        let merged = merge_intervals(vec![
+            Interval::new(10, 12),
+            Interval::new(0, 3),
+            Interval::new(4, 5),
+            Interval::new(2, 2),
+            Interval::empty(),
+            Interval::new(8, 11),
+        ]);
+        assert_eq!(merged, vec![Interval::new(0, 5), Interval::new(8, 12)]);
+        assert_eq!(merged.iter().map(|i| i.len()).sum::<usize>(), 11);
+    }
+}
@@ -1,166 +1,145 @@
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
-use std::ops::{Add, Index, IndexMut, Mul, Neg};
+use std::ops::{Index, IndexMut};
 
 use itertools::Itertools;
 use std::str::FromStr;
 
-/// A point in 2-space. Supports addition, scalar multiplication, manhattan_dist.
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub struct Point {
-    pub x: isize,
-    pub y: isize,
+mod automaton;
+mod bits;
+mod diff;
+mod export;
+mod geometry;
+mod growing;
+mod hex;
+mod interval;
+mod neighbors;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod path;
+mod pattern;
+mod point;
+mod point_n;
+mod rect;
+mod region;
+mod snapshot;
+mod sparse;
+mod svg;
+mod transform;
+mod tui;
+mod view;
+mod wrap;
+
+pub use automaton::*;
+pub use bits::*;
+pub use diff::*;
+pub use export::*;
+pub use geometry::*;
+pub use growing::*;
+pub use hex::*;
+pub use interval::*;
+pub use neighbors::*;
+pub use path::*;
+pub use pattern::*;
+pub use point::*;
+pub use point_n::*;
+pub use rect::*;
+pub use region::*;
+pub use snapshot::*;
+pub use sparse::*;
+pub use svg::*;
+pub use transform::*;
+pub use tui::*;
+pub use view::*;
+pub use wrap::*;
+
+/// A rectangular grid of glyphs, stored row by row in one `Vec`. Its top left cell is at the
+/// origin unless moved with `with_origin`, so a grid can also cover negative coordinates.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DenseStore<Glyph> {
+    data: Vec<Glyph>,
+    width: usize,
+    origin: Point,
 }
 
-impl Point {
-    pub fn manhattan_dist(&self, rhs: &Self) -> isize {
-        let mut d0 = self.x - rhs.x;
-        if d0 < Default::default() {
-            d0 = -d0;
-        }
-
-        let mut d1 = self.y - rhs.y;
-        if d1 < Default::default() {
-            d1 = -d1;
+impl<Glyph: Clone> DenseStore<Glyph> {
+    /// A grid of the given rows, which must all be the same length.
+    pub fn new(grid: &[Vec<Glyph>]) -> Self {
+        let width = grid.first().map_or(0, Vec::len);
+        assert!(
+            grid.iter().all(|row| row.len() == width),
+            "Rows differ in length"
+        );
+        DenseStore {
+            data: grid.iter().flatten().cloned().collect(),
+            width,
+            origin: Point::default(),
         }
-
-        d0 + d1
     }
 
-    pub fn rotate_deg(&self, deg: isize) -> Self {
-        match deg {
-            0 | 360 => self.clone(),
-            90 | -270 => Self {
-                x: -self.y,
-                y: self.x,
-            },
-            180 | -180 => Self {
-                x: -self.x,
-                y: -self.y,
-            },
-            270 | -90 => Self {
-                x: self.y,
-                y: -self.x,
-            },
-            _ => panic!("Bad rotation"),
+    /// A grid of the same size and origin, with each glyph replaced by `tile_mapper` of it and
+    /// its point.
+    pub fn transform<F: FnMut(&Glyph, Point) -> Glyph>(&self, mut tile_mapper: F) -> Self {
+        DenseStore {
+            data: self.tiles().map(|(g, p)| tile_mapper(g, p)).collect(),
+            width: self.width,
+            origin: self.origin,
         }
     }
 }
 
-impl Add for Point {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+impl<Glyph> DenseStore<Glyph> {
+    /// Moves the grid so its top left cell is at `origin`.
+    pub fn with_origin(mut self, origin: Point) -> Self {
+        self.origin = origin;
+        self
     }
-}
 
-impl Mul<isize> for Point {
-    type Output = Self;
-
-    fn mul(self, rhs: isize) -> Self::Output {
-        Point {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
+    /// The coordinates of the top left cell.
+    pub fn origin(&self) -> Point {
+        self.origin
     }
-}
-
-impl Neg for Point {
-    type Output = Self;
 
-    fn neg(self) -> Self::Output {
-        Point {
-            x: -self.x,
-            y: -self.y,
-        }
+    /// Where the glyph at `p` is kept in `data`, if `p` is inside the grid.
+    fn index(&self, p: Point) -> Option<usize> {
+        let Point { x, y } = p - self.origin;
+        let x = usize::try_from(x).ok().filter(|&x| x < self.width)?;
+        let y = usize::try_from(y).ok().filter(|&y| y < self.height())?;
+        Some(y * self.width + x)
     }
-}
 
-impl<T> From<&(T, T)> for Point
-where
-    T: TryInto<isize>,
-    (T, T): Clone,
-{
-    fn from(p: &(T, T)) -> Self {
-        Point::from(p.clone())
-    }
-}
-
-impl<T> From<(T, T)> for Point
-where
-    T: TryInto<isize>,
-{
-    fn from(p: (T, T)) -> Self {
-        if let Ok(x) = p.0.try_into() {
-            if let Ok(y) = p.1.try_into() {
-                return Point { x, y };
-            }
-        }
-        panic!("Could not convert to Point")
-    }
-}
-
-#[derive(Clone, Eq, PartialEq, Hash)]
-pub struct DenseStore<Glyph> {
-    data: Vec<Glyph>,
-    width: usize,
-}
-
-impl<Glyph: Clone> DenseStore<Glyph> {
-    pub fn new(grid: &Vec<Vec<Glyph>>) -> Self {
-        if grid.is_empty() {
-            DenseStore {
-                data: Vec::new(),
-                width: 0,
-            }
-        } else {
-            DenseStore {
-                data: grid.iter().flatten().cloned().collect(),
-                width: grid[0].len(),
-            }
-        }
+    fn point(origin: Point, width: usize, i: usize) -> Point {
+        origin + Point::new((i % width) as isize, (i / width) as isize)
     }
 
     pub fn get(&self, p: Point) -> Option<&Glyph> {
-        let Point { x, y } = p;
-        let width = self.width as isize;
-        if let Ok(i) = usize::try_from(x + y * width) {
-            return self.data.get(i);
-        }
-        None
+        self.index(p).map(|i| &self.data[i])
     }
 
     pub fn get_mut(&mut self, p: Point) -> Option<&mut Glyph> {
-        let Point { x, y } = p;
-        let width = self.width as isize;
-        if let Ok(i) = usize::try_from(x + y * width) {
-            return self.data.get_mut(i);
-        }
-        None
+        self.index(p).map(move |i| &mut self.data[i])
     }
 
-    pub fn tiles<'a>(&'a self) -> impl Iterator<Item = (&'a Glyph, Point)> + 'a {
-        let width = self.width;
-        self.data.iter().enumerate().map(move |(i, g)| {
-            let y = (i / width) as isize;
-            let x = (i % width) as isize;
-            let p = (x, y).into();
-            (g, p)
-        })
+    /// Every cell with its point, in reading order.
+    pub fn tiles(&self) -> impl Iterator<Item = (&Glyph, Point)> + '_ {
+        let (origin, width) = (self.origin, self.width);
+        self.data
+            .iter()
+            .enumerate()
+            .map(move |(i, g)| (g, Self::point(origin, width, i)))
     }
 
     pub fn tiles_mut(&mut self) -> impl Iterator<Item = (&mut Glyph, Point)> + '_ {
-        let width = self.width;
-        self.data.iter_mut().enumerate().map(move |(i, g)| {
-            let y = (i / width) as isize;
-            let x = (i % width) as isize;
-            let p = (x, y).into();
-            (g, p)
-        })
+        let (origin, width) = (self.origin, self.width);
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, g)| (g, Self::point(origin, width, i)))
+    }
+
+    /// The rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[Glyph]> + '_ {
+        self.data.chunks(self.width.max(1))
     }
 
     pub fn width(&self) -> usize {
@@ -168,11 +147,27 @@ impl<Glyph: Clone> DenseStore<Glyph> {
     }
 
     pub fn height(&self) -> usize {
-        self.data.len() / self.width
+        self.data.len().checked_div(self.width).unwrap_or(0)
+    }
+}
+
+impl<Glyph> Grid for DenseStore<Glyph> {
+    type Glyph = Glyph;
+
+    fn at(&self, p: Point) -> Option<&Glyph> {
+        self.get(p)
+    }
+
+    fn at_mut(&mut self, p: Point) -> Option<&mut Glyph> {
+        self.get_mut(p)
+    }
+
+    fn bounds(&self) -> Rect {
+        Rect::with_size(self.origin, self.width, self.height())
     }
 }
 
-impl<Glyph: Clone> Index<Point> for DenseStore<Glyph> {
+impl<Glyph> Index<Point> for DenseStore<Glyph> {
     type Output = Glyph;
 
     fn index(&self, index: Point) -> &Self::Output {
@@ -180,7 +175,7 @@ impl<Glyph: Clone> Index<Point> for DenseStore<Glyph> {
     }
 }
 
-impl<Glyph: Clone> IndexMut<Point> for DenseStore<Glyph> {
+impl<Glyph> IndexMut<Point> for DenseStore<Glyph> {
     fn index_mut(&mut self, index: Point) -> &mut Self::Output {
         self.get_mut(index).expect("No such point")
     }
@@ -193,8 +188,7 @@ where
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let s = self
-            .data
-            .windows(self.width)
+            .rows()
             .map(|row| {
                 row.iter()
                     .map(|g| <Glyph as Into<char>>::into(g.clone()))
@@ -212,44 +206,103 @@ where
 {
     type Err = ();
 
+    /// One row per line. Fails if the lines differ in length.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let data = parse_grid(s);
+        if data.iter().any(|row| row.len() != data[0].len()) {
+            return Err(());
+        }
         Ok(DenseStore::new(&data))
     }
 }
 
+/// Glyphs addressed by `Point`. Implementations may be finite, like `DenseStore`, or cover the
+/// whole plane, like `SparseGrid`; either way `bounds` covers every cell actually stored.
 pub trait Grid {
     type Glyph;
 
-    fn coord_transform(&self, p: Point) -> Point;
+    fn at(&self, p: Point) -> Option<&Self::Glyph>;
+
+    fn at_mut(&mut self, p: Point) -> Option<&mut Self::Glyph>;
 
-    fn data(&self) -> &Vec<Vec<Self::Glyph>>;
+    /// The smallest rectangle containing every stored cell.
+    fn bounds(&self) -> Rect;
+
+    /// Whether `p` is a cell of the grid inside its bounds.
+    fn contains(&self, p: Point) -> bool {
+        self.bounds().contains(p) && self.at(p).is_some()
+    }
 
-    fn data_mut(&mut self) -> &mut Vec<Vec<Self::Glyph>>;
+    /// Writes a glyph, returning false if `p` is not part of the grid.
+    fn set(&mut self, p: Point, g: Self::Glyph) -> bool {
+        if let Some(cell) = self.at_mut(p) {
+            *cell = g;
+            true
+        } else {
+            false
+        }
+    }
 
-    fn at(&self, p: Point) -> Option<&Self::Glyph> {
-        let Point { x, y } = self.coord_transform(p);
-        if let Ok(row) = usize::try_from(y) {
-            if let Ok(col) = usize::try_from(x) {
-                self.data()
-                    .get(row)
-                    .and_then(|row: &Vec<Self::Glyph>| row.get(col))
+    /// The cells around `p` that are part of the grid.
+    fn neighbors(
+        &self,
+        p: Point,
+        n: Neighborhood,
+    ) -> impl Iterator<Item = (Point, &Self::Glyph)> + '_ {
+        n.offsets().iter().filter_map(move |&o| {
+            let q = p + o;
+            if self.contains(q) {
+                self.at(q).map(|g| (q, g))
             } else {
                 None
             }
-        } else {
-            None
-        }
+        })
     }
 
-    fn at_mut(&mut self, p: Point) -> Option<&mut Self::Glyph> {
-        let Point { x, y } = self.coord_transform(p);
-        if let Ok(row) = usize::try_from(y) {
-            if let Ok(col) = usize::try_from(x) {
-                return Some(&mut self.data_mut()[row][col]);
+    /// The cells from `from` (exclusive) stepping by `dir` until the edge of the grid. Empty if
+    /// `dir` is zero, since the ray would never leave.
+    fn ray(&self, from: Point, dir: Point) -> impl Iterator<Item = (Point, &Self::Glyph)> + '_ {
+        let mut p = from;
+        std::iter::from_fn(move || {
+            if dir == Point::default() {
+                return None;
             }
-        }
-        None
+            p += dir;
+            if self.contains(p) {
+                self.at(p).map(|g| (p, g))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The first cell along a ray whose glyph matches `pred`.
+    fn first_in_direction<F>(
+        &self,
+        from: Point,
+        dir: Point,
+        mut pred: F,
+    ) -> Option<(Point, &Self::Glyph)>
+    where
+        F: FnMut(&Self::Glyph) -> bool,
+    {
+        self.ray(from, dir).find(|(_, g)| pred(g))
+    }
+
+    /// The first matching cell in each direction of the neighbourhood, skipping directions with
+    /// no match before the edge.
+    fn visible<'a, F>(
+        &'a self,
+        from: Point,
+        n: Neighborhood,
+        mut pred: F,
+    ) -> impl Iterator<Item = (Point, &'a Self::Glyph)> + 'a
+    where
+        F: FnMut(&Self::Glyph) -> bool + 'a,
+    {
+        n.offsets()
+            .iter()
+            .filter_map(move |&dir| self.first_in_direction(from, dir, &mut pred))
     }
 }
 
@@ -263,143 +316,10 @@ where
         .collect()
 }
 
-/// Dense Grid
-#[derive(Clone, Eq, PartialEq)]
-pub struct DenseGrid<Glyph> {
-    data: Vec<Vec<Glyph>>,
-    offset: Point,
-}
-
-impl<Glyph> DenseGrid<Glyph>
-where
-    char: Into<Glyph>,
-    Glyph: Clone,
-{
-    pub fn new(src: &str) -> Self {
-        DenseGrid {
-            data: parse_grid(src),
-            offset: Default::default(),
-        }
-    }
-
-    pub fn with_offset(mut self, origin: Point) -> Self {
-        self.offset = -origin;
-        self
-    }
-
-    pub fn enumerate_tiles(&self) -> impl Iterator<Item = (Glyph, Point)> + '_ {
-        self.data.iter().enumerate().flat_map(|(y, src_row)| {
-            src_row.iter().enumerate().map(move |(x, g)| {
-                let p = (x, y).into();
-                (g.clone(), p)
-            })
-        })
-    }
-
-    pub fn transform<F: FnMut(&Glyph, Point) -> Glyph>(&self, mut tile_mapper: F) -> Self {
-        let data = self
-            .data
-            .iter()
-            .enumerate()
-            .map(|(y, src_row)| {
-                let mut row = Vec::with_capacity(self.data[y as usize].len());
-                row.extend(src_row.iter().enumerate().map(|(x, g)| {
-                    let p = (x, y).into();
-                    tile_mapper(g, p)
-                }));
-                row
-            })
-            .collect();
-        Self {
-            data,
-            offset: self.offset,
-        }
-    }
-}
-
-impl<Glyph> Grid for DenseGrid<Glyph> {
-    type Glyph = Glyph;
-
-    fn coord_transform(&self, p: Point) -> Point {
-        p + self.offset
-    }
-
-    fn data(&self) -> &Vec<Vec<Glyph>> {
-        &self.data
-    }
-
-    fn data_mut(&mut self) -> &mut Vec<Vec<Glyph>> {
-        &mut self.data
-    }
-}
-
-impl<Glyph> Display for DenseGrid<Glyph>
-where
-    Glyph: Clone,
-    Glyph: Into<char>,
-{
-    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        let s = self
-            .data
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|g| <Glyph as Into<char>>::into(g.clone()))
-                    .collect::<String>()
-            })
-            .join("\n");
-        write!(f, "{}", s)
-    }
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::{parse_grid, Point};
-
-    #[test]
-    fn add_test() {
-        assert_eq!(
-            Point { x: 2, y: 2 } + Point { x: 3, y: 4 },
-            Point { x: 5, y: 6 }
-        );
-    }
-
-    #[test]
-    fn mul_test() {
-        assert_eq!(Point { x: 3, y: 4 } * 5, Point { x: 15, y: 20 });
-    }
+    use crate::{parse_grid, DenseStore, Grid, Point, Rect};
 
-    #[test]
-    fn into_test() {
-        let p1: Point = (2, 2).into();
-        let p2: Point = (3, 4).into();
-        assert_eq!(p1 + p2, (5, 6).into());
-    }
-
-    #[test]
-    fn manhattan_dist_test() {
-        let p0: Point = (0, 0).into();
-        let p1: Point = (1, 4).into();
-
-        assert_eq!(p0.manhattan_dist(&p1), 5);
-        assert_eq!(p1.manhattan_dist(&p0), 5);
-    }
-
-    #[test]
-    fn rotate_test() {
-        let p: Point = (1, 0).into();
-        let p90 = p.rotate_deg(90);
-        let p180 = p.rotate_deg(180);
-        let p270 = p.rotate_deg(270);
-
-        assert_eq!(p90, (0, 1).into());
-        assert_eq!(p180, (-1, 0).into());
-        assert_eq!(p270, (0, -1).into());
-
-        assert_eq!(p, p90.rotate_deg(-90));
-        assert_eq!(p, p180.rotate_deg(-180));
-        assert_eq!(p, p270.rotate_deg(-270));
-    }
     #[test]
     fn parse_grid_test() {
         #[derive(Debug, Eq, PartialEq)]
@@ -425,4 +345,45 @@ mod tests {
         assert_eq!(data[1][1], Tiles::Tree);
         assert_eq!(data[0][0], Tiles::Space);
     }
+
+    #[test]
+    fn dense_store_test() {
+        let grid: DenseStore<char> = "abc\ndef\nghi".parse().unwrap();
+        assert_eq!(grid.to_string(), "abc\ndef\nghi");
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[Point::new(2, 1)], 'f');
+
+        // Off the left edge is outside the grid, not the end of the row above.
+        assert_eq!(grid.get(Point::new(-1, 1)), None);
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.at(Point::new(0, 3)), None);
+
+        let moved = grid.clone().with_origin(Point::new(-2, -5));
+        assert_eq!(moved.origin(), Point::new(-2, -5));
+        assert_eq!(
+            moved.bounds(),
+            Rect::new(Point::new(-2, -5), Point::new(0, -3))
+        );
+        assert_eq!(moved[Point::new(-2, -5)], 'a');
+        assert_eq!(moved.get(Point::new(1, -5)), None);
+        assert_eq!(moved.tiles().nth(4), Some((&'e', Point::new(-1, -4))));
+        assert_eq!(moved.to_string(), grid.to_string());
+
+        let upper = moved.transform(|g, p| {
+            if p.y == -4 {
+                g.to_ascii_uppercase()
+            } else {
+                *g
+            }
+        });
+        assert_eq!(upper.to_string(), "abc\nDEF\nghi");
+        assert_eq!(upper.origin(), moved.origin());
+
+        assert_eq!("ab\nc".parse::<DenseStore<char>>(), Err(()));
+        let empty: DenseStore<char> = "".parse().unwrap();
+        assert_eq!(
+            (empty.to_string(), empty.bounds().is_empty()),
+            (String::new(), true)
+        );
+    }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use crate::{Grid, Point};
+
+const ORTHOGONAL: [Point; 4] = [
+    Point::new(0, -1),
+    Point::new(1, 0),
+    Point::new(0, 1),
+    Point::new(-1, 0),
+];
+
+const DIAGONAL: [Point; 4] = [
+    Point::new(-1, -1),
+    Point::new(1, -1),
+    Point::new(1, 1),
+    Point::new(-1, 1),
+];
+
+const KING: [Point; 8] = [
+    Point::new(-1, -1),
+    Point::new(0, -1),
+    Point::new(1, -1),
+    Point::new(-1, 0),
+    Point::new(1, 0),
+    Point::new(-1, 1),
+    Point::new(0, 1),
+    Point::new(1, 1),
+];
+
+/// Which of the surrounding cells count as neighbours.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Neighborhood {
+    /// The 4 cells sharing an edge.
+    Orthogonal,
+    /// The 4 cells sharing only a corner.
+    Diagonal,
+    /// All 8 surrounding cells.
+    King,
+}
+
+impl Neighborhood {
+    /// Unit offsets to each neighbour. These double as the directions for ray casts.
+    pub fn offsets(&self) -> &'static [Point] {
+        match self {
+            Neighborhood::Orthogonal => &ORTHOGONAL,
+            Neighborhood::Diagonal => &DIAGONAL,
+            Neighborhood::King => &KING,
+        }
+    }
+}
+
+/// For each cell of a grid, the first cells in each direction that match a predicate, as seen
+/// when the grid was built. Worth keeping when the matching cells don't move between generations,
+/// like seats in a waiting area.
+///
+/// Seeing isn't symmetric: a cell that doesn't match sees others, but none of them see it. So the
+/// reverse lookup is kept too.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Visibility {
+    visible: HashMap<Point, Vec<Point>>,
+    seen_by: HashMap<Point, Vec<Point>>,
+}
+
+impl Visibility {
+    pub fn new<G, F>(grid: &G, n: Neighborhood, mut pred: F) -> Self
+    where
+        G: Grid,
+        F: FnMut(&G::Glyph) -> bool,
+    {
+        let visible: HashMap<Point, Vec<Point>> = grid
+            .bounds()
+            .points()
+            .filter(|&p| grid.contains(p))
+            .map(|p| {
+                let seen = grid
+                    .visible(p, n, &mut pred)
+                    .map(|(q, _)| q)
+                    .collect::<Vec<_>>();
+                (p, seen)
+            })
+            .collect();
+        let mut seen_by: HashMap<Point, Vec<Point>> = HashMap::new();
+        for (&p, seen) in visible.iter() {
+            for &q in seen {
+                seen_by.entry(q).or_default().push(p);
+            }
+        }
+        for from in seen_by.values_mut() {
+            from.sort_unstable();
+        }
+        Visibility { visible, seen_by }
+    }
+
+    /// The cells visible from `p`, in the order of the neighbourhood's offsets.
+    pub fn visible_from(&self, p: Point) -> &[Point] {
+        self.visible.get(&p).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// The cells that `p` is visible from, in reading order.
+    pub fn visible_to(&self, p: Point) -> &[Point] {
+        self.seen_by.get(&p).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DenseStore, Grid, Neighborhood, Point, Visibility};
+
+    #[test]
+    fn neighbors_test() {
+        let grid: DenseStore<char> = "abc\ndef\nghi".parse().unwrap();
+        let chars = |n| {
+            grid.neighbors(Point::new(0, 1), n)
+                .map(|(_, &c)| c)
+                .collect::<String>()
+        };
+        assert_eq!(chars(Neighborhood::Orthogonal), "aeg");
+        assert_eq!(chars(Neighborhood::Diagonal), "bh");
+        assert_eq!(chars(Neighborhood::King), "abegh");
+        assert_eq!(
+            grid.neighbors(Point::new(1, 1), Neighborhood::King).count(),
+            8
+        );
+    }
+
+    #[test]
+    fn ray_test() {
+        let grid: DenseStore<char> = "#..#.\n.....\n..L..".parse().unwrap();
+        let ray: String = grid
+            .ray(Point::new(0, 0), Point::new(1, 0))
+            .map(|(_, &c)| c)
+            .collect();
+        assert_eq!(ray, "..#.");
+
+        let first = grid.first_in_direction(Point::new(0, 0), Point::new(1, 1), |&c| c != '.');
+        assert_eq!(first, Some((Point::new(2, 2), &'L')));
+        assert_eq!(
+            grid.first_in_direction(Point::new(0, 0), Point::new(0, 1), |&c| c != '.'),
+            None
+        );
+
+        // A ray going nowhere is empty rather than endless.
+        assert_eq!(grid.ray(Point::new(1, 1), Point::default()).count(), 0);
+        assert_eq!(
+            grid.first_in_direction(Point::new(1, 1), Point::default(), |&c| c == '?'),
+            None
+        );
+
+        let seen: Vec<Point> = grid
+            .visible(Point::new(3, 2), Neighborhood::King, |&c| c != '.')
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(seen, vec![Point::new(3, 0), Point::new(2, 2)]);
+
+        let vis = Visibility::new(&grid, Neighborhood::King, |&c| c != '.');
+        assert_eq!(vis.visible_from(Point::new(3, 2)), seen.as_slice());
+        assert_eq!(vis.visible_from(Point::new(0, 0)).len(), 2);
+        assert!(vis.visible_from(Point::new(9, 9)).is_empty());
+        assert!(vis.visible_to(Point::new(3, 0)).contains(&Point::new(3, 2)));
+        assert!(vis
+            .visible_to(Point::new(3, 0))
+            .iter()
+            .all(|&p| vis.visible_from(p).contains(&Point::new(3, 0))));
+    }
+}
@@ -0,0 +1,66 @@
+use rayon::prelude::*;
+
+use crate::{DenseStore, Point};
+
+/// Rows per band when splitting `height` rows across the thread pool. A few bands per thread
+/// keeps the threads busy when some bands are cheaper than others.
+pub(crate) fn band_rows(height: usize) -> usize {
+    (height / (rayon::current_num_threads() * 4)).max(1)
+}
+
+impl<Glyph: Clone + Send + Sync> DenseStore<Glyph> {
+    /// Splits the grid into bands of whole rows for filling in parallel. Each band comes with the
+    /// y of its first row, counted from the top of the grid.
+    pub fn par_row_bands_mut(
+        &mut self,
+        rows: usize,
+    ) -> impl IndexedParallelIterator<Item = (isize, &mut [Glyph])> {
+        let rows = rows.max(1);
+        let width = self.width.max(1);
+        self.data
+            .par_chunks_mut(width * rows)
+            .enumerate()
+            .map(move |(i, band)| ((i * rows) as isize, band))
+    }
+
+    /// Like mapping every tile in `tiles`, but computed across threads in bands of rows.
+    pub fn par_transform<F>(&self, tile_mapper: F) -> Self
+    where
+        F: Fn(&Glyph, Point) -> Glyph + Sync,
+    {
+        let mut out = self.clone();
+        let (origin, width) = (self.origin, self.width);
+        let rows = band_rows(self.height());
+        out.par_row_bands_mut(rows).for_each(|(y0, band)| {
+            for (i, cell) in band.iter_mut().enumerate() {
+                let p = origin + Point::new((i % width) as isize, y0 + (i / width) as isize);
+                *cell = tile_mapper(&self[p], p);
+            }
+        });
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DenseStore, Point};
+
+    #[test]
+    fn par_transform_test() {
+        let src: String = (0..37)
+            .map(|y| {
+                (0..23)
+                    .map(|x| if (x * 7 + y * 3) % 5 == 0 { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let flip = |&c: &char, p: Point| if c == '#' || p.x == p.y { '.' } else { '#' };
+
+        let store: DenseStore<char> = src.parse().unwrap();
+        assert_eq!(store.par_transform(flip), store.transform(flip));
+
+        let moved = store.with_origin(Point::new(-4, 9));
+        assert_eq!(moved.par_transform(flip), moved.transform(flip));
+    }
+}
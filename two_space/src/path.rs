@@ -0,0 +1,297 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::{Grid, Neighborhood, Point};
+
+/// A route through a grid, from its start to its goal inclusive.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Path {
+    pub points: Vec<Point>,
+    /// The number of steps for a breadth-first search, or the summed costs of the cells entered.
+    pub cost: usize,
+}
+
+impl Path {
+    fn trace(came_from: &HashMap<Point, Point>, start: Point, goal: Point, cost: usize) -> Self {
+        let mut points = vec![goal];
+        let mut p = goal;
+        while p != start {
+            p = came_from[&p];
+            points.push(p);
+        }
+        points.reverse();
+        Path { points, cost }
+    }
+
+    /// The number of moves, one less than the number of points.
+    pub fn len(&self) -> usize {
+        self.points.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The shortest path between two cells moving through cells whose glyphs are `passable`. The
+/// start cell itself doesn't need to be passable.
+pub fn bfs<G, F>(
+    grid: &G,
+    start: Point,
+    goal: Point,
+    n: Neighborhood,
+    mut passable: F,
+) -> Option<Path>
+where
+    G: Grid,
+    F: FnMut(&G::Glyph) -> bool,
+{
+    let mut came_from = HashMap::new();
+    let mut queue = VecDeque::new();
+    let mut dist = HashMap::new();
+    dist.insert(start, 0);
+    queue.push_back(start);
+    while let Some(p) = queue.pop_front() {
+        let d = dist[&p];
+        if p == goal {
+            return Some(Path::trace(&came_from, start, goal, d));
+        }
+        for (q, g) in grid.neighbors(p, n) {
+            if !dist.contains_key(&q) && passable(g) {
+                dist.insert(q, d + 1);
+                came_from.insert(q, p);
+                queue.push_back(q);
+            }
+        }
+    }
+    None
+}
+
+/// The number of steps from the nearest of `starts` to every reachable cell.
+pub fn bfs_distances<G, I, F>(
+    grid: &G,
+    starts: I,
+    n: Neighborhood,
+    mut passable: F,
+) -> HashMap<Point, usize>
+where
+    G: Grid,
+    I: IntoIterator<Item = Point>,
+    F: FnMut(&G::Glyph) -> bool,
+{
+    let mut dist = HashMap::new();
+    let mut queue = VecDeque::new();
+    for s in starts {
+        if dist.insert(s, 0).is_none() {
+            queue.push_back(s);
+        }
+    }
+    while let Some(p) = queue.pop_front() {
+        let d = dist[&p];
+        for (q, g) in grid.neighbors(p, n) {
+            if !dist.contains_key(&q) && passable(g) {
+                dist.insert(q, d + 1);
+                queue.push_back(q);
+            }
+        }
+    }
+    dist
+}
+
+/// Every cell that can be reached from `start`, including `start`.
+pub fn reachable<G, F>(grid: &G, start: Point, n: Neighborhood, passable: F) -> HashSet<Point>
+where
+    G: Grid,
+    F: FnMut(&G::Glyph) -> bool,
+{
+    bfs_distances(grid, Some(start), n, passable)
+        .into_keys()
+        .collect()
+}
+
+/// The cheapest path between two cells, where entering a cell costs `cost` of it, or is not
+/// allowed if that is `None`.
+pub fn dijkstra<G, F>(grid: &G, start: Point, goal: Point, n: Neighborhood, cost: F) -> Option<Path>
+where
+    G: Grid,
+    F: FnMut(Point, &G::Glyph) -> Option<usize>,
+{
+    astar(grid, start, goal, n, cost, |_| 0)
+}
+
+/// The cost of the cheapest path from `start` to every reachable cell.
+pub fn dijkstra_distances<G, F>(
+    grid: &G,
+    start: Point,
+    n: Neighborhood,
+    mut cost: F,
+) -> HashMap<Point, usize>
+where
+    G: Grid,
+    F: FnMut(Point, &G::Glyph) -> Option<usize>,
+{
+    let mut dist = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    dist.insert(start, 0);
+    heap.push(Reverse((0, start)));
+    while let Some(Reverse((d, p))) = heap.pop() {
+        if d > dist[&p] {
+            continue;
+        }
+        for (q, g) in grid.neighbors(p, n) {
+            if let Some(c) = cost(q, g) {
+                let next = d + c;
+                if dist.get(&q).is_none_or(|&old| next < old) {
+                    dist.insert(q, next);
+                    heap.push(Reverse((next, q)));
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// Like `dijkstra`, guided towards the goal by `heuristic`. The path is the cheapest as long as
+/// the heuristic never overestimates the remaining cost, as `manhattan_dist` doesn't for
+/// orthogonal moves costing at least 1.
+pub fn astar<G, F, H>(
+    grid: &G,
+    start: Point,
+    goal: Point,
+    n: Neighborhood,
+    mut cost: F,
+    mut heuristic: H,
+) -> Option<Path>
+where
+    G: Grid,
+    F: FnMut(Point, &G::Glyph) -> Option<usize>,
+    H: FnMut(Point) -> usize,
+{
+    let mut came_from = HashMap::new();
+    let mut dist = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    dist.insert(start, 0);
+    heap.push(Reverse((heuristic(start), 0, start)));
+    while let Some(Reverse((_, d, p))) = heap.pop() {
+        if p == goal {
+            return Some(Path::trace(&came_from, start, goal, d));
+        }
+        if d > dist[&p] {
+            continue;
+        }
+        for (q, g) in grid.neighbors(p, n) {
+            if let Some(c) = cost(q, g) {
+                let next = d + c;
+                if dist.get(&q).is_none_or(|&old| next < old) {
+                    dist.insert(q, next);
+                    came_from.insert(q, p);
+                    heap.push(Reverse((next + heuristic(q), next, q)));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{astar, bfs, bfs_distances, dijkstra, dijkstra_distances, reachable};
+    use crate::{DenseStore, Neighborhood, Point, SparseGrid};
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#G";
+
+    #[test]
+    fn bfs_test() {
+        let grid: DenseStore<char> = MAZE.parse().unwrap();
+        let open = |&c: &char| c != '#';
+        let start = Point::new(0, 0);
+        let goal = Point::new(7, 4);
+
+        let path = bfs(&grid, start, goal, Neighborhood::Orthogonal, open).unwrap();
+        assert_eq!(path.len(), 15);
+        assert_eq!(path.cost, 15);
+        assert_eq!(path.points.first(), Some(&start));
+        assert_eq!(path.points.last(), Some(&goal));
+        assert!(path
+            .points
+            .windows(2)
+            .all(|w| w[0].manhattan_dist(&w[1]) == 1));
+
+        assert_eq!(
+            bfs(&grid, start, goal, Neighborhood::King, open)
+                .unwrap()
+                .len(),
+            8
+        );
+        assert_eq!(
+            bfs(&grid, start, Point::new(3, 0), Neighborhood::King, open),
+            None
+        );
+
+        let both = bfs_distances(&grid, vec![start, goal], Neighborhood::Orthogonal, open);
+        assert_eq!(both[&Point::new(7, 0)], 4);
+        assert_eq!(both[&Point::new(4, 0)], 7);
+        assert_eq!(both.len(), 27);
+        assert_eq!(
+            reachable(&grid, start, Neighborhood::Orthogonal, open).len(),
+            27
+        );
+    }
+
+    #[test]
+    fn weighted_test() {
+        let grid: DenseStore<char> = "1163\n1381\n2136".parse().unwrap();
+        let cost = |_, c: &char| c.to_digit(10).map(|d| d as usize);
+        let start = Point::new(0, 0);
+        let goal = Point::new(3, 2);
+
+        let path = dijkstra(&grid, start, goal, Neighborhood::Orthogonal, cost).unwrap();
+        assert_eq!(path.cost, 13);
+        let guided = astar(&grid, start, goal, Neighborhood::Orthogonal, cost, |p| {
+            p.manhattan_dist(&goal) as usize
+        })
+        .unwrap();
+        assert_eq!(guided.cost, 13);
+
+        let dist = dijkstra_distances(&grid, start, Neighborhood::Orthogonal, cost);
+        assert_eq!(dist[&goal], 13);
+        assert_eq!(dist[&Point::new(3, 0)], 10);
+    }
+
+    #[test]
+    fn sparse_test() {
+        let mut walls = SparseGrid::new('.');
+        for y in -4..=4 {
+            walls.insert(Point::new(0, y), '#');
+        }
+        walls.insert(Point::new(-2, -4), 'o');
+        walls.insert(Point::new(2, 4), 'o');
+
+        // The wall spans the whole of the bounds, so there is no way around it.
+        let open = |&c: &char| c != '#';
+        assert_eq!(
+            bfs(
+                &walls,
+                Point::new(-1, 0),
+                Point::new(1, 0),
+                Neighborhood::Orthogonal,
+                open
+            ),
+            None
+        );
+        walls.insert(Point::new(0, 3), '.');
+        let path = bfs(
+            &walls,
+            Point::new(-1, 0),
+            Point::new(1, 0),
+            Neighborhood::Orthogonal,
+            open,
+        );
+        assert_eq!(path.map(|p| p.len()), Some(8));
+    }
+}
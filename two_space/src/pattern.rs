@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+
+use crate::{bounding_box, Grid, Point, Transform};
+
+/// A rectangular template to search a grid for. Cells of the template that are wildcards match
+/// anything; the rest must equal the grid's glyph.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Pattern<Glyph> {
+    /// The non-wildcard cells, as offsets from the top left corner, in reading order.
+    cells: Vec<(Point, Glyph)>,
+    width: usize,
+    height: usize,
+}
+
+/// Where a pattern was found, and in which orientation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Match {
+    /// The top left corner of the oriented pattern on the grid.
+    pub origin: Point,
+    pub transform: Transform,
+}
+
+impl<Glyph: Clone + PartialEq> Pattern<Glyph> {
+    /// Builds a template from lines of text, mapping each character to a glyph, or to `None` for
+    /// a wildcard.
+    pub fn parse<F: FnMut(char) -> Option<Glyph>>(s: &str, mut glyph: F) -> Self {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+        for (y, line) in s.lines().enumerate() {
+            height = y + 1;
+            width = width.max(line.chars().count());
+            for (x, ch) in line.chars().enumerate() {
+                if let Some(g) = glyph(ch) {
+                    cells.push((Point::new(x as isize, y as isize), g));
+                }
+            }
+        }
+        Pattern {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of non-wildcard cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The template turned or mirrored by `t`, with its top left corner moved back to the origin.
+    pub fn oriented(&self, t: Transform) -> Self {
+        let corners = [
+            Point::new(0, 0),
+            Point::new(self.width as isize - 1, self.height as isize - 1),
+        ];
+        let bounds = bounding_box(corners.iter().map(|&p| p.transform(t)));
+        let mut cells: Vec<_> = self
+            .cells
+            .iter()
+            .map(|(p, g)| (p.transform(t) - bounds.min, g.clone()))
+            .collect();
+        cells.sort_by_key(|(p, _)| *p);
+        Pattern {
+            cells,
+            width: bounds.width(),
+            height: bounds.height(),
+        }
+    }
+
+    /// Whether the template matches with its top left corner at `origin`.
+    pub fn matches_at<G: Grid<Glyph = Glyph>>(&self, grid: &G, origin: Point) -> bool {
+        self.cells
+            .iter()
+            .all(|(p, g)| grid.at(origin + *p) == Some(g))
+    }
+
+    /// The top left corners of every match in this orientation, overlapping or not, in reading
+    /// order.
+    pub fn find<G: Grid<Glyph = Glyph>>(&self, grid: &G) -> Vec<Point> {
+        let bounds = grid.bounds();
+        if bounds.width() < self.width || bounds.height() < self.height {
+            return Vec::new();
+        }
+        let last = bounds.max - Point::new(self.width as isize - 1, self.height as isize - 1);
+        (bounds.min.y..=last.y)
+            .flat_map(|y| (bounds.min.x..=last.x).map(move |x| Point::new(x, y)))
+            .filter(|&p| self.matches_at(grid, p))
+            .collect()
+    }
+
+    /// Every match in any of the 8 orientations. Orientations that leave a symmetric template
+    /// unchanged are only searched once.
+    pub fn find_oriented<G: Grid<Glyph = Glyph>>(&self, grid: &G) -> Vec<Match> {
+        let mut seen = Vec::new();
+        let mut ret = Vec::new();
+        for &t in Transform::ALL.iter() {
+            let oriented = self.oriented(t);
+            if seen.contains(&oriented) {
+                continue;
+            }
+            ret.extend(oriented.find(grid).into_iter().map(|origin| Match {
+                origin,
+                transform: t,
+            }));
+            seen.push(oriented);
+        }
+        ret
+    }
+
+    /// The grid cells a match covers, wildcards excepted.
+    pub fn covered(&self, m: &Match) -> impl Iterator<Item = Point> {
+        let origin = m.origin;
+        self.oriented(m.transform)
+            .cells
+            .into_iter()
+            .map(move |(p, _)| origin + p)
+    }
+
+    /// Overwrites the cells covered by each match with `marker`, returning how many distinct
+    /// cells were marked. Overlapping matches mark shared cells once.
+    pub fn stamp<G: Grid<Glyph = Glyph>>(
+        &self,
+        grid: &mut G,
+        matches: &[Match],
+        marker: Glyph,
+    ) -> usize {
+        let cells: HashSet<Point> = matches.iter().flat_map(|m| self.covered(m)).collect();
+        cells
+            .iter()
+            .filter(|&&p| grid.set(p, marker.clone()))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DenseStore, Match, Pattern, Point, Transform};
+
+    fn hook() -> Pattern<char> {
+        Pattern::parse("#.\n##\n?#", |c| if c == '.' { None } else { Some(c) })
+    }
+
+    #[test]
+    fn find_test() {
+        let grid: DenseStore<char> = "..#.\n..##\n..?#".parse().unwrap();
+        // The match touches the last row and column.
+        assert_eq!(hook().find(&grid), vec![Point::new(2, 0)]);
+        assert_eq!(hook().len(), 5);
+
+        let turned = hook().oriented(Transform::ROTATE_90);
+        assert_eq!((turned.width(), turned.height()), (3, 2));
+        assert_eq!(turned.find(&grid), Vec::<Point>::new());
+    }
+
+    #[test]
+    fn oriented_test() {
+        let grid: DenseStore<char> = "....\n?##.\n##..\n....".parse().unwrap();
+        let found = hook().find_oriented(&grid);
+        assert_eq!(found.len(), 1);
+        let m = found[0];
+        assert_eq!(m.origin, Point::new(0, 1));
+        assert!(hook().oriented(m.transform).matches_at(&grid, m.origin));
+
+        // A square of four is the same in every orientation.
+        let square = Pattern::parse("##\n##", Some);
+        let grid: DenseStore<char> = "###\n###".parse().unwrap();
+        let found = square.find_oriented(&grid);
+        assert_eq!(found.len(), 2);
+
+        let mut marked = grid.clone();
+        assert_eq!(square.stamp(&mut marked, &found, 'O'), 6);
+        assert_eq!(marked, "OOO\nOOO".parse().unwrap());
+        let one = Match {
+            origin: Point::new(1, 0),
+            transform: Transform::IDENTITY,
+        };
+        let mut marked = grid;
+        assert_eq!(square.stamp(&mut marked, &[one], 'O'), 4);
+        assert_eq!(marked, "#OO\n#OO".parse().unwrap());
+    }
+}
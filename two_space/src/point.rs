@@ -0,0 +1,355 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+
+use num_traits::{NumCast, PrimInt, Signed, ToPrimitive};
+
+/// A point in 2-space over any primitive integer type, `isize` by default. Supports the usual
+/// vector arithmetic (scalar multiplication and division are component-wise), dot and cross
+/// products, and manhattan, chebyshev and squared euclidean distances.
+///
+/// Points are ordered in reading order: by `y`, then by `x`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point<T = isize> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A coordinate did not fit in the target integer type.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct OutOfRange;
+
+impl Display for OutOfRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Coordinate out of range")
+    }
+}
+
+impl Error for OutOfRange {}
+
+fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: PrimInt> Point<T> {
+    /// Builds a point from coordinates of another integer type.
+    pub fn try_new<U: ToPrimitive>(x: U, y: U) -> Result<Self, OutOfRange> {
+        match (NumCast::from(x), NumCast::from(y)) {
+            (Some(x), Some(y)) => Ok(Point { x, y }),
+            _ => Err(OutOfRange),
+        }
+    }
+
+    /// Converts to a point over another integer type.
+    pub fn try_cast<U: PrimInt + NumCast>(self) -> Result<Point<U>, OutOfRange> {
+        Point::try_new(self.x, self.y)
+    }
+
+    pub fn manhattan_dist(&self, rhs: &Self) -> T {
+        abs_diff(self.x, rhs.x) + abs_diff(self.y, rhs.y)
+    }
+
+    /// Distance when diagonal steps cost the same as orthogonal ones.
+    pub fn chebyshev_dist(&self, rhs: &Self) -> T {
+        abs_diff(self.x, rhs.x).max(abs_diff(self.y, rhs.y))
+    }
+
+    pub fn euclidean_dist_sq(&self, rhs: &Self) -> T {
+        let dx = abs_diff(self.x, rhs.x);
+        let dy = abs_diff(self.y, rhs.y);
+        dx * dx + dy * dy
+    }
+
+    pub fn dot(&self, rhs: &Self) -> T {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    /// The z component of the 3-space cross product; positive when `rhs` is counterclockwise
+    /// from `self`.
+    pub fn cross(&self, rhs: &Self) -> T {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
+    pub fn component_mul(self, rhs: Self) -> Self {
+        Point {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+        }
+    }
+
+    pub fn component_div(self, rhs: Self) -> Self {
+        Point {
+            x: self.x / rhs.x,
+            y: self.y / rhs.y,
+        }
+    }
+
+    pub fn component_min(self, rhs: Self) -> Self {
+        Point {
+            x: self.x.min(rhs.x),
+            y: self.y.min(rhs.y),
+        }
+    }
+
+    pub fn component_max(self, rhs: Self) -> Self {
+        Point {
+            x: self.x.max(rhs.x),
+            y: self.y.max(rhs.y),
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> Point<T> {
+    pub fn abs(self) -> Self {
+        Point {
+            x: self.x.abs(),
+            y: self.y.abs(),
+        }
+    }
+
+    /// Each component reduced to -1, 0 or 1; a unit step towards this point.
+    pub fn signum(self) -> Self {
+        Point {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+}
+
+impl<T: Ord> PartialOrd for Point<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Point<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.y.cmp(&other.y).then_with(|| self.x.cmp(&other.x))
+    }
+}
+
+impl<T: PrimInt> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl<T: PrimInt> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl<T: PrimInt> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl<T: PrimInt> Div<T> for Point<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Point {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+impl<T: PrimInt> Rem<T> for Point<T> {
+    type Output = Self;
+
+    fn rem(self, rhs: T) -> Self::Output {
+        Point {
+            x: self.x % rhs,
+            y: self.y % rhs,
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<T: PrimInt> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: PrimInt> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: PrimInt> MulAssign<T> for Point<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: PrimInt> DivAssign<T> for Point<T> {
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T: PrimInt> RemAssign<T> for Point<T> {
+    fn rem_assign(&mut self, rhs: T) {
+        *self = *self % rhs;
+    }
+}
+
+impl<T: Copy> From<&(T, T)> for Point<T> {
+    fn from(p: &(T, T)) -> Self {
+        Point { x: p.0, y: p.1 }
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from(p: (T, T)) -> Self {
+        Point { x: p.0, y: p.1 }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(p: Point<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{OutOfRange, Point};
+
+    #[test]
+    fn add_test() {
+        assert_eq!(
+            Point { x: 2, y: 2 } + Point { x: 3, y: 4 },
+            Point { x: 5, y: 6 }
+        );
+    }
+
+    #[test]
+    fn mul_test() {
+        assert_eq!(Point { x: 3, y: 4 } * 5, Point { x: 15, y: 20 });
+    }
+
+    #[test]
+    fn into_test() {
+        let p1: Point = (2, 2).into();
+        let p2: Point = (3, 4).into();
+        assert_eq!(p1 + p2, (5, 6).into());
+    }
+
+    #[test]
+    fn ops_test() {
+        let mut p: Point<i32> = Point::new(7, -9);
+        assert_eq!(p - Point::new(2, 1), Point::new(5, -10));
+        assert_eq!(p / 2, Point::new(3, -4));
+        assert_eq!(p % 4, Point::new(3, -1));
+        assert_eq!(-p, Point::new(-7, 9));
+        assert_eq!(p.abs(), Point::new(7, 9));
+        assert_eq!(p.signum(), Point::new(1, -1));
+
+        p += Point::new(1, 1);
+        p *= 3;
+        p -= Point::new(4, 0);
+        p /= 2;
+        assert_eq!(p, Point::new(10, -12));
+
+        let q: Point<u8> = Point::new(3, 4);
+        assert_eq!(q.dot(&Point::new(2, 1)), 10);
+        assert_eq!(q.component_mul(Point::new(2, 3)), Point::new(6, 12));
+        assert_eq!(Point::new(1, 0).cross(&Point::new(0, 1)), 1);
+    }
+
+    #[test]
+    fn ord_test() {
+        let mut points: Vec<Point> = vec![(2, 1).into(), (0, 2).into(), (1, 1).into()];
+        points.sort();
+        assert_eq!(points, vec![(1, 1).into(), (2, 1).into(), (0, 2).into()]);
+    }
+
+    #[test]
+    fn convert_test() {
+        assert_eq!(Point::<u8>::try_new(3usize, 4usize), Ok(Point::new(3, 4)));
+        assert_eq!(Point::<u8>::try_new(-1isize, 4), Err(OutOfRange));
+        assert_eq!(Point::new(300i32, 0).try_cast::<u8>(), Err(OutOfRange));
+        assert_eq!(Point::new(3u64, 2).try_cast(), Ok(Point::<i8>::new(3, 2)));
+    }
+
+    #[test]
+    fn manhattan_dist_test() {
+        let p0: Point = (0, 0).into();
+        let p1: Point = (1, 4).into();
+
+        assert_eq!(p0.manhattan_dist(&p1), 5);
+        assert_eq!(p1.manhattan_dist(&p0), 5);
+    }
+
+    #[test]
+    fn dist_test() {
+        let p0: Point<u32> = Point::new(5, 1);
+        let p1: Point<u32> = Point::new(2, 5);
+
+        assert_eq!(p0.manhattan_dist(&p1), 7);
+        assert_eq!(p0.chebyshev_dist(&p1), 4);
+        assert_eq!(p0.euclidean_dist_sq(&p1), 25);
+    }
+
+    #[test]
+    fn rotate_test() {
+        let p: Point = (1, 0).into();
+        let p90 = p.rotate_deg(90);
+        let p180 = p.rotate_deg(180);
+        let p270 = p.rotate_deg(270);
+
+        assert_eq!(p90, (0, 1).into());
+        assert_eq!(p180, (-1, 0).into());
+        assert_eq!(p270, (0, -1).into());
+
+        assert_eq!(p, p90.rotate_deg(-90));
+        assert_eq!(p, p180.rotate_deg(-180));
+        assert_eq!(p, p270.rotate_deg(-270));
+    }
+}
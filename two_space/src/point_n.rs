@@ -0,0 +1,205 @@
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+use crate::Point;
+
+/// A point in N-space, for cellular automata and puzzles beyond the plane. Neighbours are the
+/// points one unit step away in up to some number of axes at once: the 2N that share a face, those
+/// that also share an edge, or all 3^N - 1 around it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointN<const N: usize>(pub [isize; N]);
+
+impl<const N: usize> PointN<N> {
+    pub const fn new(coords: [isize; N]) -> Self {
+        PointN(coords)
+    }
+
+    pub const fn origin() -> Self {
+        PointN([0; N])
+    }
+
+    /// Unit offsets that change between 1 and `max_axes` coordinates.
+    pub fn offsets(max_axes: usize) -> Vec<Self> {
+        let mut ret = Vec::new();
+        let mut offset = [-1; N];
+        loop {
+            let changed = offset.iter().filter(|&&c| c != 0).count();
+            if changed > 0 && changed <= max_axes {
+                ret.push(PointN(offset));
+            }
+
+            // Count through every combination of -1, 0, 1 like an odometer.
+            let mut i = 0;
+            while i < N && offset[i] == 1 {
+                offset[i] = -1;
+                i += 1;
+            }
+            if i == N {
+                return ret;
+            }
+            offset[i] += 1;
+        }
+    }
+
+    /// Points one step away in up to `max_axes` coordinates.
+    pub fn neighbors_within(&self, max_axes: usize) -> impl Iterator<Item = Self> {
+        let p = *self;
+        Self::offsets(max_axes).into_iter().map(move |o| p + o)
+    }
+
+    /// The 2N points sharing a face with this one.
+    pub fn face_neighbors(&self) -> impl Iterator<Item = Self> {
+        self.neighbors_within(1)
+    }
+
+    /// The points sharing a face or an edge with this one.
+    pub fn edge_neighbors(&self) -> impl Iterator<Item = Self> {
+        self.neighbors_within(2)
+    }
+
+    /// All 3^N - 1 surrounding points.
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> {
+        self.neighbors_within(N)
+    }
+
+    pub fn manhattan_dist(&self, rhs: &Self) -> isize {
+        self.0
+            .iter()
+            .zip(rhs.0.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum()
+    }
+
+    pub fn chebyshev_dist(&self, rhs: &Self) -> isize {
+        self.0
+            .iter()
+            .zip(rhs.0.iter())
+            .map(|(a, b)| (a - b).abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn zip_with<F: Fn(isize, isize) -> isize>(self, rhs: Self, f: F) -> Self {
+        let mut ret = self;
+        for (a, b) in ret.0.iter_mut().zip(rhs.0.iter()) {
+            *a = f(*a, *b);
+        }
+        ret
+    }
+}
+
+impl<const N: usize> Default for PointN<N> {
+    fn default() -> Self {
+        Self::origin()
+    }
+}
+
+impl<const N: usize> Index<usize> for PointN<N> {
+    type Output = isize;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for PointN<N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<const N: usize> Add for PointN<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl<const N: usize> Sub for PointN<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl<const N: usize> Mul<isize> for PointN<N> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: isize) -> Self::Output {
+        self.0.iter_mut().for_each(|a| *a *= rhs);
+        self
+    }
+}
+
+impl<const N: usize> Neg for PointN<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self * -1
+    }
+}
+
+impl<const N: usize> AddAssign for PointN<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const N: usize> SubAssign for PointN<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const N: usize> From<[isize; N]> for PointN<N> {
+    fn from(coords: [isize; N]) -> Self {
+        PointN(coords)
+    }
+}
+
+impl From<Point> for PointN<2> {
+    fn from(p: Point) -> Self {
+        PointN([p.x, p.y])
+    }
+}
+
+impl From<PointN<2>> for Point {
+    fn from(p: PointN<2>) -> Self {
+        Point::new(p[0], p[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, PointN};
+    use std::collections::HashSet;
+
+    #[test]
+    fn neighbor_count_test() {
+        let p = PointN([4, -2, 7]);
+        assert_eq!(p.face_neighbors().count(), 6);
+        assert_eq!(p.edge_neighbors().count(), 18);
+        assert_eq!(p.neighbors().count(), 26);
+        assert_eq!(PointN([0; 4]).neighbors().count(), 80);
+
+        let unique: HashSet<_> = p.neighbors().collect();
+        assert_eq!(unique.len(), 26);
+        assert!(!unique.contains(&p));
+        assert!(unique.iter().all(|n| n.chebyshev_dist(&p) == 1));
+        assert!(p.face_neighbors().all(|n| n.manhattan_dist(&p) == 1));
+    }
+
+    #[test]
+    fn arith_test() {
+        let a = PointN([1, 2, 3, 4]);
+        let b = PointN([4, 3, 2, 1]);
+        assert_eq!(a + b, PointN([5; 4]));
+        assert_eq!(a - b, PointN([-3, -1, 1, 3]));
+        assert_eq!(-a * 2, PointN([-2, -4, -6, -8]));
+        assert_eq!(a.manhattan_dist(&b), 8);
+
+        let p: Point = PointN([3, -1]).into();
+        assert_eq!(p, Point::new(3, -1));
+    }
+}
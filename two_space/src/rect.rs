@@ -0,0 +1,179 @@
+use crate::{Interval, Point};
+
+/// An axis-aligned rectangle of lattice points, `min` to `max` inclusive. A rectangle with `max`
+/// below `min` on either axis is empty.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Rect {
+    pub fn new(min: Point, max: Point) -> Self {
+        Rect { min, max }
+    }
+
+    /// The rectangle with its top left at `origin` and the given size.
+    pub fn with_size(origin: Point, width: usize, height: usize) -> Self {
+        Rect {
+            min: origin,
+            max: origin + Point::new(width as isize - 1, height as isize - 1),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Rect {
+            min: Point::new(0, 0),
+            max: Point::new(-1, -1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max.x < self.min.x || self.max.y < self.min.y
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1).max(0) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1).max(0) as usize
+    }
+
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.min.x <= p.x && p.x <= self.max.x && self.min.y <= p.y && p.y <= self.max.y
+    }
+
+    /// Whether every point of `other` is also in this one. The empty rectangle is inside every
+    /// rectangle.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.is_empty() || (self.contains(other.min) && self.contains(other.max))
+    }
+
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The points in both, which may be empty.
+    pub fn intersection(&self, other: &Rect) -> Self {
+        let r = Rect::new(
+            self.min.component_max(other.min),
+            self.max.component_min(other.max),
+        );
+        if r.is_empty() {
+            Rect::empty()
+        } else {
+            r
+        }
+    }
+
+    pub fn x_interval(&self) -> Interval {
+        Interval::new(self.min.x, self.max.x)
+    }
+
+    pub fn y_interval(&self) -> Interval {
+        Interval::new(self.min.y, self.max.y)
+    }
+
+    /// The smallest rectangle containing this one and `p`.
+    pub fn including(&self, p: Point) -> Self {
+        if self.is_empty() {
+            Rect::new(p, p)
+        } else {
+            Rect::new(self.min.component_min(p), self.max.component_max(p))
+        }
+    }
+
+    /// The smallest rectangle containing both.
+    pub fn union(&self, other: &Rect) -> Self {
+        if self.is_empty() {
+            *other
+        } else if other.is_empty() {
+            *self
+        } else {
+            Rect::new(
+                self.min.component_min(other.min),
+                self.max.component_max(other.max),
+            )
+        }
+    }
+
+    /// Grows by `n` cells on every side, or shrinks for negative `n`.
+    pub fn expand(&self, n: isize) -> Self {
+        if self.is_empty() {
+            *self
+        } else {
+            Rect::new(self.min - Point::new(n, n), self.max + Point::new(n, n))
+        }
+    }
+
+    /// Every point inside, in reading order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let Rect { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+impl Default for Rect {
+    fn default() -> Self {
+        Rect::empty()
+    }
+}
+
+/// The bounding box of a set of points.
+pub fn bounding_box<I: IntoIterator<Item = Point>>(points: I) -> Rect {
+    points
+        .into_iter()
+        .fold(Rect::empty(), |r, p| r.including(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bounding_box, Interval, Point, Rect};
+
+    #[test]
+    fn rect_test() {
+        let r = bounding_box(vec![Point::new(2, -1), Point::new(-1, 3), Point::new(0, 0)]);
+        assert_eq!(r, Rect::new(Point::new(-1, -1), Point::new(2, 3)));
+        assert_eq!((r.width(), r.height(), r.area()), (4, 5, 20));
+        assert_eq!(r.points().count(), 20);
+        assert_eq!(r.points().next(), Some(Point::new(-1, -1)));
+        assert!(r.contains(Point::new(2, 3)));
+        assert!(!r.contains(Point::new(3, 3)));
+
+        assert!(Rect::empty().is_empty());
+        assert_eq!(Rect::empty().points().count(), 0);
+        assert_eq!(Rect::empty().union(&r), r);
+        assert_eq!(r.expand(1).area(), 42);
+        assert_eq!(
+            Rect::with_size(Point::new(1, 1), 3, 2).max,
+            Point::new(3, 2)
+        );
+    }
+
+    #[test]
+    fn intersection_test() {
+        let a = Rect::new(Point::new(0, 0), Point::new(4, 3));
+        let b = Rect::new(Point::new(2, -5), Point::new(9, 1));
+        assert_eq!(
+            a.intersection(&b),
+            Rect::new(Point::new(2, 0), Point::new(4, 1))
+        );
+        assert!(a.overlaps(&b));
+        assert!(a.contains_rect(&a.intersection(&b)));
+        assert!(!a.contains_rect(&b));
+        assert!(a.union(&b).contains_rect(&b));
+        assert_eq!(a.y_interval(), Interval::new(0, 3));
+        assert_eq!(b.x_interval().len(), b.width());
+
+        let c = Rect::with_size(Point::new(5, 0), 2, 2);
+        assert!(!a.overlaps(&c));
+        assert!(a.intersection(&c).is_empty());
+        assert!(a.contains_rect(&Rect::empty()));
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{bounding_box, Grid, Neighborhood, Point, Rect};
+
+/// A connected set of cells sharing a glyph.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Region<Glyph> {
+    pub glyph: Glyph,
+    pub cells: HashSet<Point>,
+}
+
+impl<Glyph> Region<Glyph> {
+    /// The region containing `start`: every cell with the same glyph connected to it through `n`.
+    pub fn at<G>(grid: &G, start: Point, n: Neighborhood) -> Option<Self>
+    where
+        G: Grid<Glyph = Glyph>,
+        Glyph: Clone + PartialEq,
+    {
+        let glyph = grid.at(start)?.clone();
+        let mut cells = HashSet::new();
+        cells.insert(start);
+        let mut stack = vec![start];
+        while let Some(p) = stack.pop() {
+            for (q, g) in grid.neighbors(p, n) {
+                if *g == glyph && cells.insert(q) {
+                    stack.push(q);
+                }
+            }
+        }
+        Some(Region { glyph, cells })
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains(&p)
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The number of cell edges between the region and the cells around it.
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .flat_map(|&p| {
+                Neighborhood::Orthogonal
+                    .offsets()
+                    .iter()
+                    .map(move |&o| p + o)
+            })
+            .filter(|q| !self.cells.contains(q))
+            .count()
+    }
+
+    pub fn bounds(&self) -> Rect {
+        bounding_box(self.cells.iter().copied())
+    }
+
+    /// The number of straight edges around the region, including those of any holes. This is
+    /// the same as the number of corners.
+    pub fn sides(&self) -> usize {
+        let ortho = Neighborhood::Orthogonal.offsets();
+        self.cells
+            .iter()
+            .map(|&p| {
+                // Each pair of adjacent directions, N and E, E and S, and so on, meets at a corner.
+                (0..ortho.len())
+                    .filter(|&i| {
+                        let a = ortho[i];
+                        let b = ortho[(i + 1) % ortho.len()];
+                        let has_a = self.contains(p + a);
+                        let has_b = self.contains(p + b);
+                        let convex = !has_a && !has_b;
+                        let concave = has_a && has_b && !self.contains(p + a + b);
+                        convex || concave
+                    })
+                    .count()
+            })
+            .sum()
+    }
+}
+
+/// The connected components of a grid, numbered in the reading order of their first cell.
+#[derive(Debug, Clone)]
+pub struct Components<Glyph> {
+    regions: Vec<Region<Glyph>>,
+    labels: HashMap<Point, usize>,
+}
+
+impl<Glyph: Clone + PartialEq> Components<Glyph> {
+    /// Splits every cell of the grid into regions of equal glyphs connected through `n`. Use
+    /// `Neighborhood::Orthogonal` for 4-connectivity and `Neighborhood::King` for 8.
+    pub fn new<G: Grid<Glyph = Glyph>>(grid: &G, n: Neighborhood) -> Self {
+        let mut regions = Vec::new();
+        let mut labels = HashMap::new();
+        for p in grid.bounds().points() {
+            if labels.contains_key(&p) || !grid.contains(p) {
+                continue;
+            }
+            if let Some(region) = Region::at(grid, p, n) {
+                for &q in region.cells.iter() {
+                    labels.insert(q, regions.len());
+                }
+                regions.push(region);
+            }
+        }
+        Components { regions, labels }
+    }
+
+    /// The number of the region containing `p`.
+    pub fn label(&self, p: Point) -> Option<usize> {
+        self.labels.get(&p).copied()
+    }
+
+    pub fn region(&self, label: usize) -> &Region<Glyph> {
+        &self.regions[label]
+    }
+
+    pub fn regions(&self) -> &[Region<Glyph>] {
+        &self.regions
+    }
+
+    /// The regions made of `glyph`.
+    pub fn of<'a>(&'a self, glyph: &'a Glyph) -> impl Iterator<Item = &'a Region<Glyph>> + 'a {
+        self.regions.iter().filter(move |r| r.glyph == *glyph)
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+/// Replaces the glyph of every cell connected to `start` through `n` that shares its glyph,
+/// returning the number of cells filled.
+pub fn flood_fill<G>(grid: &mut G, start: Point, n: Neighborhood, fill: G::Glyph) -> usize
+where
+    G: Grid,
+    G::Glyph: Clone + PartialEq,
+{
+    match Region::at(grid, start, n) {
+        Some(region) => {
+            for &p in region.cells.iter() {
+                grid.set(p, fill.clone());
+            }
+            region.area()
+        }
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{flood_fill, Components, DenseStore, Neighborhood, Point, Rect, Region};
+
+    const GARDEN: &str = "\
+AAAA
+BBCD
+BBCC
+EEEC";
+
+    #[test]
+    fn region_test() {
+        let grid: DenseStore<char> = GARDEN.parse().unwrap();
+        let components = Components::new(&grid, Neighborhood::Orthogonal);
+        assert_eq!(components.len(), 5);
+        assert_eq!(components.label(Point::new(0, 0)), Some(0));
+        assert_eq!(components.label(Point::new(3, 3)), Some(2));
+
+        let stats: Vec<_> = components
+            .regions()
+            .iter()
+            .map(|r| (r.glyph, r.area(), r.perimeter(), r.sides()))
+            .collect();
+        assert_eq!(
+            stats,
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4)
+            ]
+        );
+        assert_eq!(
+            components.region(2).bounds(),
+            Rect::new(Point::new(2, 1), Point::new(3, 3))
+        );
+    }
+
+    #[test]
+    fn connectivity_test() {
+        let mut grid: DenseStore<char> = "#..#\n.#.#\n..#.\n####".parse().unwrap();
+        let four = Components::new(&grid, Neighborhood::Orthogonal);
+        let eight = Components::new(&grid, Neighborhood::King);
+        assert_eq!(four.of(&'#').count(), 4);
+        assert_eq!(eight.of(&'#').count(), 1);
+
+        // A ring of 8 around a hole of 1 has 4 sides outside and 4 inside.
+        let ring: DenseStore<char> = "ooo\no.o\nooo".parse().unwrap();
+        let r = Region::at(&ring, Point::new(0, 0), Neighborhood::Orthogonal).unwrap();
+        assert_eq!((r.area(), r.perimeter(), r.sides()), (8, 16, 8));
+
+        assert_eq!(
+            flood_fill(&mut grid, Point::new(1, 0), Neighborhood::Orthogonal, '~'),
+            3
+        );
+        assert_eq!(grid, "#~~#\n.#~#\n..#.\n####".parse().unwrap());
+        assert_eq!(
+            flood_fill(&mut grid, Point::new(9, 9), Neighborhood::King, '~'),
+            0
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{DenseStore, Grid, Point, Rect, SparseGrid};
+
+/// A copy of the cells of any grid, which writes itself as text that says what it holds:
+///
+/// ```text
+/// size 3x2
+/// origin -1,4
+/// legend # FILLED
+/// legend L SEAT
+///
+/// #L#
+/// LL
+/// ```
+///
+/// The header gives the width and height, the coordinates of the top left cell, and the name of
+/// each glyph's character, and ends with a blank line before the rows. Cells missing from the
+/// grid are written as spaces, and rows may stop short where the rest is missing. If a glyph is
+/// itself written as a space, a `holes` line after the origin names another character to mark
+/// the missing cells instead. The names in the legend are for people; reading turns each
+/// character back into a glyph with `Into`, and only checks that it is in the legend.
+///
+/// Snapshots of grids of different types compare equal if they have the same cells in the same
+/// places, so they make a convenient way to check one against another in tests.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Snapshot<Glyph> {
+    origin: Point,
+    width: usize,
+    height: usize,
+    /// In reading order.
+    cells: Vec<Option<Glyph>>,
+}
+
+impl<Glyph: Clone> Snapshot<Glyph> {
+    /// Copies every cell inside the grid's bounds.
+    pub fn of<G: Grid<Glyph = Glyph>>(grid: &G) -> Self {
+        let bounds = grid.bounds();
+        let origin = if bounds.is_empty() {
+            Point::default()
+        } else {
+            bounds.min
+        };
+        Snapshot {
+            origin,
+            width: bounds.width(),
+            height: bounds.height(),
+            cells: bounds.points().map(|p| grid.at(p).cloned()).collect(),
+        }
+    }
+
+    /// The coordinates of the top left cell.
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        if Grid::bounds(self).contains(p) {
+            let Point { x, y } = p - self.origin;
+            Some(y as usize * self.width + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// A dense grid with the same cells at the same coordinates, filling any missing cells with
+    /// `missing`.
+    pub fn to_dense(&self, missing: Glyph) -> DenseStore<Glyph> {
+        let rows: Vec<Vec<Glyph>> = self
+            .cells
+            .chunks(self.width.max(1))
+            .map(|row| {
+                row.iter()
+                    .map(|g| g.clone().unwrap_or_else(|| missing.clone()))
+                    .collect()
+            })
+            .collect();
+        DenseStore::new(&rows).with_origin(self.origin)
+    }
+
+    /// A sparse grid with the same cells, leaving out those equal to `default`.
+    pub fn to_sparse(&self, default: Glyph) -> SparseGrid<Glyph>
+    where
+        Glyph: PartialEq,
+    {
+        let mut ret = SparseGrid::new(default);
+        for p in Grid::bounds(self).points() {
+            if let Some(g) = self.at(p) {
+                ret.insert(p, g.clone());
+            }
+        }
+        ret
+    }
+}
+
+impl<Glyph: Clone> Grid for Snapshot<Glyph> {
+    type Glyph = Glyph;
+
+    fn at(&self, p: Point) -> Option<&Glyph> {
+        self.index(p).and_then(|i| self.cells[i].as_ref())
+    }
+
+    fn at_mut(&mut self, p: Point) -> Option<&mut Glyph> {
+        self.index(p).and_then(move |i| self.cells[i].as_mut())
+    }
+
+    fn bounds(&self) -> Rect {
+        Rect::with_size(self.origin, self.width, self.height)
+    }
+}
+
+impl<Glyph> Display for Snapshot<Glyph>
+where
+    Glyph: Clone + Debug + Into<char>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut legend: Vec<(char, String)> = Vec::new();
+        for g in self.cells.iter().flatten() {
+            let ch = g.clone().into();
+            if !legend.iter().any(|(c, _)| *c == ch) {
+                legend.push((ch, format!("{:?}", g)));
+            }
+        }
+        legend.sort();
+        let used = |ch: &char| legend.iter().any(|(c, _)| c == ch);
+        let hole = if used(&' ') {
+            ('!'..=char::MAX).find(|c| !used(c)).unwrap_or(' ')
+        } else {
+            ' '
+        };
+
+        writeln!(f, "size {}x{}", self.width, self.height)?;
+        writeln!(f, "origin {},{}", self.origin.x, self.origin.y)?;
+        if hole != ' ' {
+            writeln!(f, "holes {}", hole)?;
+        }
+        for (ch, name) in legend.iter() {
+            writeln!(f, "legend {} {}", ch, name)?;
+        }
+        writeln!(f)?;
+        for row in self.cells.chunks(self.width.max(1)) {
+            let line: String = row
+                .iter()
+                .map(|g| g.clone().map_or(hole, Into::into))
+                .collect();
+            writeln!(f, "{}", line.trim_end_matches(hole))?;
+        }
+        Ok(())
+    }
+}
+
+/// What was wrong with a snapshot's text, with the line number where it was found.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SnapshotError {
+    /// A header line was missing or malformed.
+    Header(usize),
+    /// A cell's character isn't in the legend.
+    Glyph(usize, char),
+    /// The rows don't fit the size in the header.
+    Size(usize),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Header(line) => write!(f, "Bad snapshot header on line {}", line),
+            SnapshotError::Glyph(line, ch) => {
+                write!(f, "Glyph {:?} on line {} is not in the legend", ch, line)
+            }
+            SnapshotError::Size(line) => {
+                write!(f, "Snapshot rows don't fit its size at line {}", line)
+            }
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+fn header<'a>(line: Option<(usize, &'a str)>, key: &str) -> Result<&'a str, SnapshotError> {
+    match line {
+        Some((n, l)) => l
+            .strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix(' '))
+            .ok_or(SnapshotError::Header(n)),
+        None => Err(SnapshotError::Header(0)),
+    }
+}
+
+fn pair<T: FromStr>(s: &str, sep: char, line: usize) -> Result<(T, T), SnapshotError> {
+    let bad = || SnapshotError::Header(line);
+    let (a, b) = s.split_once(sep).ok_or_else(bad)?;
+    Ok((a.parse().map_err(|_| bad())?, b.parse().map_err(|_| bad())?))
+}
+
+impl<Glyph> FromStr for Snapshot<Glyph>
+where
+    Glyph: Clone,
+    char: Into<Glyph>,
+{
+    type Err = SnapshotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Numbered from 1, as an editor would.
+        let mut lines = s.lines().enumerate().map(|(i, l)| (i + 1, l)).peekable();
+        let size = lines.next();
+        let (width, height) = pair(header(size, "size")?, 'x', size.map_or(0, |l| l.0))?;
+        let origin = lines.next();
+        let (x, y) = pair(header(origin, "origin")?, ',', origin.map_or(0, |l| l.0))?;
+
+        let mut hole = ' ';
+        if let Some(&(n, line)) = lines.peek() {
+            if line.starts_with("holes") {
+                let mut chars = header(Some((n, line)), "holes")?.chars();
+                hole = match (chars.next(), chars.next()) {
+                    (Some(ch), None) => ch,
+                    _ => return Err(SnapshotError::Header(n)),
+                };
+                lines.next();
+            }
+        }
+
+        let mut legend = Vec::new();
+        for (n, line) in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            let mut chars = header(Some((n, line)), "legend")?.chars();
+            match chars.next() {
+                Some(ch) if ch != hole => legend.push(ch),
+                _ => return Err(SnapshotError::Header(n)),
+            }
+        }
+
+        let mut cells = Vec::with_capacity(width * height);
+        let mut last = 0;
+        for (n, line) in lines {
+            last = n;
+            let row: Vec<char> = line.chars().collect();
+            if row.len() > width || cells.len() == width * height {
+                return Err(SnapshotError::Size(n));
+            }
+            for x in 0..width {
+                cells.push(match row.get(x) {
+                    None => None,
+                    Some(&ch) if ch == hole => None,
+                    Some(ch) if legend.contains(ch) => Some((*ch).into()),
+                    Some(&ch) => return Err(SnapshotError::Glyph(n, ch)),
+                });
+            }
+        }
+        if cells.len() != width * height {
+            return Err(SnapshotError::Size(last + 1));
+        }
+        Ok(Snapshot {
+            origin: Point::new(x, y),
+            width,
+            height,
+            cells,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{DenseStore, Point, SparseGrid};
+
+    #[derive(Serialize)]
+    #[serde(rename = "DenseStore")]
+    struct DenseStoreRef<'a, Glyph> {
+        origin: Point,
+        width: usize,
+        cells: &'a [Glyph],
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "DenseStore")]
+    struct DenseStoreData<Glyph> {
+        #[serde(default)]
+        origin: Point,
+        width: usize,
+        cells: Vec<Glyph>,
+    }
+
+    /// The coordinates of the top left cell, the width and the cells in reading order. The
+    /// origin may be left out when reading.
+    impl<Glyph: Serialize> Serialize for DenseStore<Glyph> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            DenseStoreRef {
+                origin: self.origin,
+                width: self.width,
+                cells: &self.data,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de, Glyph: Deserialize<'de>> Deserialize<'de> for DenseStore<Glyph> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let DenseStoreData {
+                origin,
+                width,
+                cells,
+            } = DenseStoreData::deserialize(deserializer)?;
+            let fits = if width == 0 {
+                cells.is_empty()
+            } else {
+                cells.len() % width == 0
+            };
+            if !fits {
+                return Err(D::Error::custom("cells don't make whole rows"));
+            }
+            Ok(DenseStore {
+                data: cells,
+                width,
+                origin,
+            })
+        }
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename = "SparseGrid")]
+    struct SparseGridRef<'a, Glyph> {
+        default: &'a Glyph,
+        cells: Vec<(Point, &'a Glyph)>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "SparseGrid")]
+    struct SparseGridData<Glyph> {
+        default: Glyph,
+        cells: Vec<(Point, Glyph)>,
+    }
+
+    /// The default glyph and the stored cells as a list in reading order, since most formats
+    /// can't key a map by a point.
+    impl<Glyph: Serialize + Clone + PartialEq> Serialize for SparseGrid<Glyph> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut cells: Vec<_> = self.occupied().collect();
+            cells.sort_by_key(|(p, _)| *p);
+            SparseGridRef {
+                default: self.default_glyph(),
+                cells,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de, Glyph> Deserialize<'de> for SparseGrid<Glyph>
+    where
+        Glyph: Deserialize<'de> + Clone + PartialEq,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let SparseGridData { default, cells } = SparseGridData::deserialize(deserializer)?;
+            let mut ret = SparseGrid::new(default);
+            for (p, g) in cells {
+                ret.insert(p, g);
+            }
+            Ok(ret)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Automaton, DenseStore, Grid, Neighborhood, Point, Snapshot, SnapshotError};
+
+    fn life(g: &char, n: &mut dyn Iterator<Item = &char>) -> char {
+        match (g, n.filter(|&&c| c == '#').count()) {
+            (_, 3) | ('#', 2) => '#',
+            _ => '.',
+        }
+    }
+
+    #[test]
+    fn text_test() {
+        let grid = "#.\n.#\n##"
+            .parse::<DenseStore<char>>()
+            .unwrap()
+            .with_origin(Point::new(-1, 4));
+        let snap = Snapshot::of(&grid);
+        let text = snap.to_string();
+        assert_eq!(
+            text,
+            "size 2x3\norigin -1,4\nlegend # '#'\nlegend . '.'\n\n#.\n.#\n##\n"
+        );
+        let back: Snapshot<char> = text.parse().unwrap();
+        assert_eq!(back, snap);
+        assert_eq!(back.to_dense('?'), grid);
+        assert_eq!(back.at(Point::new(0, 5)), Some(&'#'));
+
+        // Missing cells are written as spaces, and short rows; a dense copy fills them in.
+        let holes: Snapshot<char> = "size 2x2\norigin 0,0\nlegend # '#'\nlegend x 'x'\n\n# \nx#"
+            .parse()
+            .unwrap();
+        assert_eq!(holes.at(Point::new(1, 0)), None);
+        assert!(Snapshot::of(&holes).to_string().ends_with("\n\n#\nx#\n"));
+        assert_eq!(holes.to_dense('.'), "#.\nx#".parse().unwrap());
+
+        // Holes anywhere, even whole rows, read back as holes.
+        let text = "size 3x3\norigin 2,-1\nlegend # '#'\n\n# #\n\n ##\n";
+        let holes: Snapshot<char> = text.parse().unwrap();
+        assert_eq!(holes.at(Point::new(3, -1)), None);
+        assert_eq!(holes.at(Point::new(3, 0)), None);
+        assert_eq!(holes.at(Point::new(4, 1)), Some(&'#'));
+        assert_eq!(holes.to_string(), text);
+        assert_eq!(holes.to_string().parse::<Snapshot<char>>().unwrap(), holes);
+
+        // Where a glyph is a space, holes are marked with another character.
+        let text = "size 3x2\norigin 0,0\nholes !\nlegend   ' '\nlegend a 'a'\n\na \n!!a\n";
+        let spaced: Snapshot<char> = text.parse().unwrap();
+        assert_eq!(spaced.at(Point::new(1, 0)), Some(&' '));
+        assert_eq!(spaced.at(Point::new(2, 0)), None);
+        assert_eq!(spaced.at(Point::new(0, 1)), None);
+        assert_eq!(spaced.to_string(), text);
+        let grid: DenseStore<char> = "a b\n!  ".parse().unwrap();
+        let text = Snapshot::of(&grid).to_string();
+        assert!(text.starts_with("size 3x2\norigin 0,0\nholes \"\n"));
+        assert_eq!(text.parse::<Snapshot<char>>().unwrap().to_dense('?'), grid);
+
+        let bad = |s: &str| s.parse::<Snapshot<char>>().unwrap_err();
+        assert_eq!(
+            bad("size 2x1\norigin 0,0\n\n##"),
+            SnapshotError::Glyph(4, '#')
+        );
+        assert_eq!(bad("size 2x1\norigin 0\n\n"), SnapshotError::Header(2));
+        assert_eq!(
+            bad("size 1x1\norigin 0,0\nlegend   ' '\n\n "),
+            SnapshotError::Header(3)
+        );
+        assert_eq!(
+            bad("size 1x1\norigin 0,0\nholes #\nlegend # x\n\n#"),
+            SnapshotError::Header(4)
+        );
+        assert_eq!(
+            bad("size 1x1\norigin 0,0\nholes\n\n#"),
+            SnapshotError::Header(3)
+        );
+        assert_eq!(
+            bad("size 2x2\norigin 0,0\nlegend # x\n\n##"),
+            SnapshotError::Size(6)
+        );
+        assert_eq!(
+            bad("size 1x1\norigin 0,0\nlegend # x\n\n##"),
+            SnapshotError::Size(5)
+        );
+    }
+
+    #[test]
+    fn resume_test() {
+        let start: DenseStore<char> = ".#...\n..#..\n###..\n.....\n.....".parse().unwrap();
+        let mut whole = Automaton::new(start.clone(), Neighborhood::King, life);
+        whole.run(4);
+
+        let mut first = Automaton::new(start, Neighborhood::King, life);
+        first.run(2);
+        let saved = Snapshot::of(first.grid()).to_string();
+        let loaded: Snapshot<char> = saved.parse().unwrap();
+        let mut second = Automaton::new(loaded.to_dense('.'), Neighborhood::King, life);
+        second.run(2);
+        assert_eq!(Snapshot::of(second.grid()), Snapshot::of(whole.grid()));
+
+        // A glider moved on by 4 generations is the same shape one cell down and right.
+        let sparse = Snapshot::of(whole.grid()).to_sparse('.');
+        let glider = Snapshot::of(&DenseStore::<char>::new(&[
+            vec!['.', '#', '.'],
+            vec!['.', '.', '#'],
+            vec!['#', '#', '#'],
+        ]))
+        .to_sparse('.');
+        assert_eq!(sparse.len(), 5);
+        assert!(sparse
+            .occupied()
+            .all(|(p, _)| glider.get(p - Point::new(1, 1)) == &'#'));
+        assert_eq!(sparse.bounds().min, Point::new(1, 1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        use crate::SparseGrid;
+
+        let store: DenseStore<char> = "#.\n.#\n##".parse().unwrap();
+        let store = store.with_origin(Point::new(3, -2));
+        let json = serde_json::to_string(&store).unwrap();
+        assert_eq!(
+            json,
+            r##"{"origin":{"x":3,"y":-2},"width":2,"cells":["#",".",".","#","#","#"]}"##
+        );
+        assert_eq!(
+            serde_json::from_str::<DenseStore<char>>(&json).unwrap(),
+            store
+        );
+        assert!(
+            serde_json::from_str::<DenseStore<char>>(r##"{"width":2,"cells":["#"]}"##).is_err()
+        );
+        let at_origin: DenseStore<char> =
+            serde_json::from_str(r#"{"width":1,"cells":["a","b"]}"#).unwrap();
+        assert_eq!(at_origin, "a\nb".parse().unwrap());
+
+        let mut sparse = SparseGrid::new('.');
+        sparse.insert(Point::new(5, -5), '#');
+        sparse.insert(Point::new(-1, 0), '#');
+        let json = serde_json::to_string(&sparse).unwrap();
+        assert_eq!(
+            json,
+            r##"{"default":".","cells":[[{"x":5,"y":-5},"#"],[{"x":-1,"y":0},"#"]]}"##
+        );
+        assert_eq!(
+            serde_json::from_str::<SparseGrid<char>>(&json).unwrap(),
+            sparse
+        );
+    }
+}
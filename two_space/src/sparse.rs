@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+
+use crate::{DenseStore, Grid, Point, Rect};
+
+/// A grid over the whole plane, storing only the cells that differ from a default glyph. Keeps
+/// track of the bounding box of the stored cells as they change.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SparseGrid<Glyph> {
+    cells: HashMap<Point, Glyph>,
+    default: Glyph,
+    bounds: Rect,
+}
+
+impl<Glyph: Clone + PartialEq> SparseGrid<Glyph> {
+    pub fn new(default: Glyph) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: Rect::empty(),
+        }
+    }
+
+    /// Copies every non-default cell of a dense grid.
+    pub fn from_dense(store: &DenseStore<Glyph>, default: Glyph) -> Self {
+        let mut ret = Self::new(default);
+        for (g, p) in store.tiles() {
+            ret.insert(p, g.clone());
+        }
+        ret
+    }
+
+    /// A dense copy covering `bounds()`, with every cell at the same point.
+    pub fn to_dense(&self) -> DenseStore<Glyph> {
+        let rows: Vec<Vec<Glyph>> = (self.bounds.min.y..=self.bounds.max.y)
+            .map(|y| {
+                (self.bounds.min.x..=self.bounds.max.x)
+                    .map(|x| self.get(Point::new(x, y)).clone())
+                    .collect()
+            })
+            .collect();
+        DenseStore::new(&rows).with_origin(self.bounds.min)
+    }
+
+    pub fn default_glyph(&self) -> &Glyph {
+        &self.default
+    }
+
+    /// The glyph at `p`, which is the default glyph for any cell not stored.
+    pub fn get(&self, p: Point) -> &Glyph {
+        self.cells.get(&p).unwrap_or(&self.default)
+    }
+
+    /// Stores a glyph, returning the one it replaces. Storing the default glyph removes the cell.
+    pub fn insert(&mut self, p: Point, g: Glyph) -> Option<Glyph> {
+        if g == self.default {
+            self.remove(p)
+        } else {
+            self.bounds = self.bounds.including(p);
+            self.cells.insert(p, g)
+        }
+    }
+
+    pub fn remove(&mut self, p: Point) -> Option<Glyph> {
+        let ret = self.cells.remove(&p);
+        if ret.is_some() && self.on_edge(p) {
+            self.recompute_bounds();
+        }
+        ret
+    }
+
+    /// Drops stored cells that have been set back to the default through `at_mut`.
+    pub fn prune(&mut self) {
+        let default = &self.default;
+        self.cells.retain(|_, g| g != default);
+        self.recompute_bounds();
+    }
+
+    /// The number of stored cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Iterates over the stored cells in no particular order.
+    pub fn occupied(&self) -> impl Iterator<Item = (Point, &Glyph)> + '_ {
+        self.cells.iter().map(|(p, g)| (*p, g))
+    }
+
+    fn on_edge(&self, p: Point) -> bool {
+        p.x == self.bounds.min.x
+            || p.x == self.bounds.max.x
+            || p.y == self.bounds.min.y
+            || p.y == self.bounds.max.y
+    }
+
+    fn recompute_bounds(&mut self) {
+        self.bounds = crate::bounding_box(self.cells.keys().copied());
+    }
+}
+
+impl<Glyph: Clone + PartialEq> Grid for SparseGrid<Glyph> {
+    type Glyph = Glyph;
+
+    fn at(&self, p: Point) -> Option<&Glyph> {
+        Some(self.get(p))
+    }
+
+    /// Stores the cell if it isn't already, so it counts as occupied even if it is left at the
+    /// default; see `prune`.
+    fn at_mut(&mut self, p: Point) -> Option<&mut Glyph> {
+        self.bounds = self.bounds.including(p);
+        let default = &self.default;
+        Some(self.cells.entry(p).or_insert_with(|| default.clone()))
+    }
+
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    fn set(&mut self, p: Point, g: Glyph) -> bool {
+        self.insert(p, g);
+        true
+    }
+}
+
+impl<Glyph> Display for SparseGrid<Glyph>
+where
+    Glyph: Clone + PartialEq,
+    Glyph: Into<char>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let s = (self.bounds.min.y..=self.bounds.max.y)
+            .map(|y| {
+                (self.bounds.min.x..=self.bounds.max.x)
+                    .map(|x| <Glyph as Into<char>>::into(self.get(Point::new(x, y)).clone()))
+                    .collect::<String>()
+            })
+            .join("\n");
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DenseStore, Grid, Point, Rect, SparseGrid};
+
+    #[test]
+    fn sparse_test() {
+        let mut grid = SparseGrid::new('.');
+        grid.insert(Point::new(-2, 1), '#');
+        grid.insert(Point::new(3, -4), '#');
+        grid.insert(Point::new(0, 0), '.');
+        assert_eq!(grid.len(), 2);
+        assert_eq!(
+            grid.bounds(),
+            Rect::new(Point::new(-2, -4), Point::new(3, 1))
+        );
+        assert_eq!(grid.at(Point::new(100, 100)), Some(&'.'));
+
+        grid.set(Point::new(3, -4), '.');
+        assert_eq!(grid.len(), 1);
+        assert_eq!(
+            grid.bounds(),
+            Rect::new(Point::new(-2, 1), Point::new(-2, 1))
+        );
+
+        *grid.at_mut(Point::new(0, 0)).unwrap() = 'x';
+        *grid.at_mut(Point::new(5, 5)).unwrap() = '.';
+        assert_eq!(grid.len(), 3);
+        grid.prune();
+        assert_eq!(grid.len(), 2);
+        assert_eq!(
+            grid.bounds(),
+            Rect::new(Point::new(-2, 0), Point::new(0, 1))
+        );
+        assert_eq!(grid.to_string(), "..x\n#..");
+    }
+
+    #[test]
+    fn dense_round_trip_test() {
+        let dense: DenseStore<char> = ".#.\n..#\n###".parse().unwrap();
+        let sparse = SparseGrid::from_dense(&dense, '.');
+        assert_eq!(sparse.len(), 5);
+        assert_eq!(sparse.occupied().filter(|(p, _)| p.y == 2).count(), 3);
+        assert_eq!(sparse.to_dense(), dense);
+
+        let moved = dense.with_origin(Point::new(-4, 7));
+        let sparse = SparseGrid::from_dense(&moved, '.');
+        assert_eq!(sparse.bounds().min, Point::new(-4, 7));
+        assert_eq!(sparse.to_dense(), moved);
+        assert_eq!(SparseGrid::from_dense(&sparse.to_dense(), '.'), sparse);
+    }
+}
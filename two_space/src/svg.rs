@@ -0,0 +1,156 @@
+use std::fmt::{Display, Formatter, Write};
+
+use crate::{Hex, Orientation, Point, Rgb};
+
+/// A vector drawing of points, lines, labels and hex cells, written out as an SVG document
+/// through `Display`. The view box grows to fit whatever is drawn.
+#[derive(Debug, Clone, Default)]
+pub struct Svg {
+    y_up: bool,
+    /// min x, min y, max x, max y of everything drawn so far.
+    extent: Option<(f64, f64, f64, f64)>,
+    elements: Vec<String>,
+}
+
+impl Svg {
+    /// A drawing with y pointing down, as on a grid.
+    pub fn new() -> Self {
+        Svg::default()
+    }
+
+    /// A drawing with y pointing up, as on a map with north at the top.
+    pub fn y_up() -> Self {
+        Svg {
+            y_up: true,
+            ..Svg::default()
+        }
+    }
+
+    fn place(&mut self, x: f64, y: f64, margin: f64) -> (f64, f64) {
+        // Subtracting rather than negating keeps 0 from printing as -0.
+        let y = if self.y_up { 0.0 - y } else { y };
+        let (x0, y0, x1, y1) = (x - margin, y - margin, x + margin, y + margin);
+        self.extent = Some(match self.extent {
+            None => (x0, y0, x1, y1),
+            Some((a, b, c, d)) => (a.min(x0), b.min(y0), c.max(x1), d.max(y1)),
+        });
+        (x, y)
+    }
+
+    /// A line through each point in turn, like the route of a ship.
+    pub fn polyline<I: IntoIterator<Item = Point>>(&mut self, points: I, colour: Rgb) -> &mut Self {
+        let mut coords = String::new();
+        for p in points {
+            let (x, y) = self.place(p.x as f64, p.y as f64, 0.0);
+            if !coords.is_empty() {
+                coords.push(' ');
+            }
+            write!(coords, "{},{}", x, y).unwrap();
+        }
+        self.elements.push(format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5" vector-effect="non-scaling-stroke"/>"#,
+            coords, colour
+        ));
+        self
+    }
+
+    pub fn point(&mut self, p: Point, radius: f64, colour: Rgb) -> &mut Self {
+        let (x, y) = self.place(p.x as f64, p.y as f64, radius);
+        self.elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            x, y, radius, colour
+        ));
+        self
+    }
+
+    /// Text centred on `p`, `size` units high.
+    pub fn label(&mut self, p: Point, text: &str, size: f64, colour: Rgb) -> &mut Self {
+        let (x, y) = self.place(p.x as f64, p.y as f64, size);
+        self.elements.push(format!(
+            r#"<text x="{}" y="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+            x,
+            y,
+            size,
+            colour,
+            escape(text)
+        ));
+        self
+    }
+
+    /// A hexagon with corners `size` from its centre, placed as by `Hex::centre`.
+    pub fn hex(&mut self, hex: Hex, orientation: Orientation, size: f64, fill: Rgb) -> &mut Self {
+        let mut corners = String::new();
+        for &(x, y) in hex.corners(orientation, size).iter() {
+            // Hex rows are laid out with y down, whichever way the drawing points.
+            let (x, y) = self.place(x, if self.y_up { 0.0 - y } else { y }, 0.0);
+            if !corners.is_empty() {
+                corners.push(' ');
+            }
+            write!(corners, "{:.3},{:.3}", x, y).unwrap();
+        }
+        self.elements.push(format!(
+            r##"<polygon points="{}" fill="{}" stroke="#000000" stroke-width="1" vector-effect="non-scaling-stroke"/>"##,
+            corners, fill
+        ));
+        self
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl Display for Svg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (x0, y0, x1, y1) = self.extent.unwrap_or((0.0, 0.0, 0.0, 0.0));
+        // Leave a little room so strokes at the edge aren't cut in half.
+        let pad = ((x1 - x0).max(y1 - y0) * 0.02).max(1.0);
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            x0 - pad,
+            y0 - pad,
+            x1 - x0 + 2.0 * pad,
+            y1 - y0 + 2.0 * pad
+        )?;
+        for e in self.elements.iter() {
+            writeln!(f, "  {}", e)?;
+        }
+        write!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Hex, Orientation, Point, Rgb, Svg};
+
+    #[test]
+    fn svg_test() {
+        let mut svg = Svg::y_up();
+        svg.polyline(
+            vec![Point::new(0, 0), Point::new(10, 0), Point::new(10, 5)],
+            Rgb(0, 0, 255),
+        )
+        .label(Point::new(0, 0), "start & <end>", 2.0, Rgb::BLACK);
+        let doc = svg.to_string();
+        assert!(doc.starts_with("<svg xmlns"));
+        assert!(doc.contains(r#"points="0,0 10,0 10,-5""#));
+        assert!(doc.contains(r##"stroke="#0000ff""##));
+        assert!(doc.contains("start &amp; &lt;end&gt;"));
+        assert!(doc.ends_with("</svg>"));
+        assert!(doc.contains(r#"viewBox="-3 -6 14 9""#));
+    }
+
+    #[test]
+    fn hex_test() {
+        let mut svg = Svg::new();
+        svg.hex(Hex::default(), Orientation::Pointy, 10.0, Rgb::WHITE)
+            .hex(Hex::new(1, 0), Orientation::Flat, 10.0, Rgb::WHITE);
+        let doc = svg.to_string();
+        assert_eq!(doc.matches("<polygon").count(), 2);
+        assert!(doc.contains("8.660,-5.000 8.660,5.000 0.000,10.000"));
+        assert!(doc.contains("25.000,8.660"));
+    }
+}
@@ -0,0 +1,316 @@
+use num_traits::{PrimInt, Signed};
+
+use crate::{DenseStore, Point};
+
+/// One of the 8 symmetries of the square (the dihedral group D4): a reflection across the y axis
+/// if `flip` is set, followed by `quarter_turns` counterclockwise rotations.
+///
+/// Rotations are counterclockwise with y pointing up, matching `Point::rotate_deg`. On a grid,
+/// where y points down the screen, the same rotation appears clockwise.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Transform {
+    quarter_turns: u8,
+    flip: bool,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform::new(0, false);
+    pub const ROTATE_90: Transform = Transform::new(1, false);
+    pub const ROTATE_180: Transform = Transform::new(2, false);
+    pub const ROTATE_270: Transform = Transform::new(3, false);
+    /// Mirror left to right: x -> -x.
+    pub const FLIP_X: Transform = Transform::new(0, true);
+    /// Mirror top to bottom: y -> -y.
+    pub const FLIP_Y: Transform = Transform::new(2, true);
+    /// Swap x and y.
+    pub const TRANSPOSE: Transform = Transform::new(3, true);
+    /// Reflect across the other diagonal: (x, y) -> (-y, -x).
+    pub const ANTI_TRANSPOSE: Transform = Transform::new(1, true);
+
+    /// Every symmetry, each exactly once.
+    pub const ALL: [Transform; 8] = [
+        Transform::new(0, false),
+        Transform::new(1, false),
+        Transform::new(2, false),
+        Transform::new(3, false),
+        Transform::new(0, true),
+        Transform::new(1, true),
+        Transform::new(2, true),
+        Transform::new(3, true),
+    ];
+
+    pub const fn new(quarter_turns: u8, flip: bool) -> Self {
+        Transform {
+            quarter_turns: quarter_turns % 4,
+            flip,
+        }
+    }
+
+    /// A counterclockwise rotation by any multiple of 90 degrees, or `None` for other angles.
+    pub fn rotation(deg: isize) -> Option<Self> {
+        if deg % 90 == 0 {
+            Some(Transform::new((deg / 90).rem_euclid(4) as u8, false))
+        } else {
+            None
+        }
+    }
+
+    pub fn quarter_turns(&self) -> u8 {
+        self.quarter_turns
+    }
+
+    pub fn is_flip(&self) -> bool {
+        self.flip
+    }
+
+    /// Whether this swaps the roles of width and height.
+    pub fn swaps_axes(&self) -> bool {
+        self.quarter_turns % 2 == 1
+    }
+
+    pub fn apply<T: PrimInt + Signed>(&self, p: Point<T>) -> Point<T> {
+        let p = if self.flip { Point::new(-p.x, p.y) } else { p };
+        match self.quarter_turns {
+            0 => p,
+            1 => Point::new(-p.y, p.x),
+            2 => Point::new(-p.x, -p.y),
+            _ => Point::new(p.y, -p.x),
+        }
+    }
+
+    /// The transform equivalent to applying `self`, then `next`.
+    pub fn then(&self, next: Transform) -> Self {
+        // Rotating then reflecting is the same as reflecting then rotating the other way.
+        let turns = if next.flip {
+            4 - self.quarter_turns
+        } else {
+            self.quarter_turns
+        };
+        Transform::new(turns + next.quarter_turns, self.flip != next.flip)
+    }
+
+    pub fn inverse(&self) -> Self {
+        if self.flip {
+            *self
+        } else {
+            Transform::new(4 - self.quarter_turns, false)
+        }
+    }
+
+    /// Turns to the left, counterclockwise with y pointing up.
+    pub fn turn_left() -> Self {
+        Self::ROTATE_90
+    }
+
+    /// Turns to the right, clockwise with y pointing up.
+    pub fn turn_right() -> Self {
+        Self::ROTATE_270
+    }
+}
+
+impl<T: PrimInt + Signed> Point<T> {
+    /// Rotates counterclockwise (with y pointing up) by a multiple of 90 degrees.
+    ///
+    /// Panics for any other angle; see `try_rotate_deg`.
+    pub fn rotate_deg(&self, deg: isize) -> Self {
+        self.try_rotate_deg(deg).expect("Bad rotation")
+    }
+
+    /// Rotates counterclockwise by a multiple of 90 degrees, or `None` for other angles, which
+    /// would leave the lattice.
+    pub fn try_rotate_deg(&self, deg: isize) -> Option<Self> {
+        Transform::rotation(deg).map(|t| t.apply(*self))
+    }
+
+    pub fn transform(&self, t: Transform) -> Self {
+        t.apply(*self)
+    }
+
+    /// Rotates 90 degrees counterclockwise with y pointing up.
+    pub fn turn_left(&self) -> Self {
+        Transform::turn_left().apply(*self)
+    }
+
+    /// Rotates 90 degrees clockwise with y pointing up.
+    pub fn turn_right(&self) -> Self {
+        Transform::turn_right().apply(*self)
+    }
+}
+
+impl<Glyph: Clone> DenseStore<Glyph> {
+    /// A copy of this grid with its contents moved by `t`, positioned with its top left cell
+    /// where this one's is. With y pointing down, `Transform::ROTATE_90` turns the picture
+    /// clockwise.
+    pub fn transformed(&self, t: Transform) -> Self {
+        let (w, h) = (self.width as isize, self.height() as isize);
+        let new_width = if t.swaps_axes() {
+            self.height()
+        } else {
+            self.width
+        };
+
+        // The source's corners land around the origin; shift the lowest back onto it.
+        let shift = [(0, 0), (w - 1, 0), (0, h - 1), (w - 1, h - 1)]
+            .iter()
+            .map(|&c| t.apply(c.into()))
+            .fold(Point::default(), Point::component_min);
+
+        let mut cells: Vec<Option<Glyph>> = vec![None; self.data.len()];
+        for (g, p) in self.tiles() {
+            let q = t.apply(p - self.origin) - shift;
+            cells[q.x as usize + q.y as usize * new_width] = Some(g.clone());
+        }
+        DenseStore {
+            data: cells.into_iter().map(Option::unwrap).collect(),
+            width: new_width,
+            origin: self.origin,
+        }
+    }
+
+    /// Moves the contents by `t` in place, like `transformed`. Each symmetry is a transpose
+    /// followed by flips, none of which needs a second copy of the grid.
+    pub fn transform_in_place(&mut self, t: Transform) {
+        let (a, b) = (t.apply(Point::new(1, 0)), t.apply(Point::new(0, 1)));
+        let (flip_x, flip_y) = if t.swaps_axes() {
+            self.transpose();
+            (b.x < 0, a.y < 0)
+        } else {
+            (a.x < 0, b.y < 0)
+        };
+        if flip_x {
+            self.flip_x();
+        }
+        if flip_y {
+            self.flip_y();
+        }
+    }
+
+    /// Turns the picture a quarter turn clockwise in place, with y pointing down.
+    pub fn rotate_cw(&mut self) {
+        self.transform_in_place(Transform::ROTATE_90);
+    }
+
+    /// Mirrors left to right in place.
+    pub fn flip_x(&mut self) {
+        for row in self.data.chunks_mut(self.width.max(1)) {
+            row.reverse();
+        }
+    }
+
+    /// Mirrors top to bottom in place.
+    pub fn flip_y(&mut self) {
+        let (w, h) = (self.width, self.height());
+        for y in 0..h / 2 {
+            let (top, bottom) = self.data.split_at_mut((h - 1 - y) * w);
+            top[y * w..(y + 1) * w].swap_with_slice(&mut bottom[..w]);
+        }
+    }
+
+    /// Swaps rows and columns in place. The top left cell stays where it is.
+    pub fn transpose(&mut self) {
+        let (w, h) = (self.width, self.height());
+        // Follow each cycle of the permutation, so a grid that isn't square also needs only a
+        // bit per cell rather than a second copy.
+        let mut done = vec![0u64; self.data.len().div_ceil(64)];
+        for start in 0..self.data.len() {
+            let mut i = start;
+            while done[i / 64] & 1 << (i % 64) == 0 {
+                done[i / 64] |= 1 << (i % 64);
+                let to = (i % w) * h + i / w;
+                if to != start {
+                    self.data.swap(start, to);
+                }
+                i = to;
+            }
+        }
+        self.width = h;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DenseStore, Point, Transform};
+
+    #[test]
+    fn group_test() {
+        let p: Point = (2, 1).into();
+        for a in Transform::ALL.iter() {
+            assert_eq!(a.inverse().apply(a.apply(p)), p);
+            assert_eq!(a.then(a.inverse()), Transform::IDENTITY);
+            for b in Transform::ALL.iter() {
+                assert_eq!(a.then(*b).apply(p), b.apply(a.apply(p)));
+            }
+        }
+
+        let images: Vec<Point> = Transform::ALL.iter().map(|t| t.apply(p)).collect();
+        for (i, q) in images.iter().enumerate() {
+            assert!(!images[i + 1..].contains(q));
+        }
+
+        assert_eq!(Transform::FLIP_Y.apply(p), (2, -1).into());
+        assert_eq!(Transform::TRANSPOSE.apply(p), (1, 2).into());
+        assert_eq!(Transform::ANTI_TRANSPOSE.apply(p), (-1, -2).into());
+        assert_eq!(
+            Transform::FLIP_X.then(Transform::FLIP_Y),
+            Transform::ROTATE_180
+        );
+    }
+
+    #[test]
+    fn turn_test() {
+        let north: Point = (0, 1).into();
+        assert_eq!(north.turn_right(), (1, 0).into());
+        assert_eq!(north.turn_left(), (-1, 0).into());
+        assert_eq!(north.rotate_deg(450), north.turn_left());
+        assert_eq!(north.rotate_deg(-630), north.turn_left());
+        assert_eq!(north.try_rotate_deg(45), None);
+    }
+
+    #[test]
+    fn grid_test() {
+        let grid: DenseStore<char> = "abc\ndef".parse().unwrap();
+        let rotated = grid.transformed(Transform::ROTATE_90);
+        assert_eq!(rotated, "da\neb\nfc".parse().unwrap());
+        assert_eq!(
+            grid.transformed(Transform::FLIP_X),
+            "cba\nfed".parse().unwrap()
+        );
+        assert_eq!(
+            grid.transformed(Transform::TRANSPOSE),
+            "ad\nbe\ncf".parse().unwrap()
+        );
+        for t in Transform::ALL.iter() {
+            assert_eq!(grid.transformed(*t).transformed(t.inverse()), grid);
+        }
+    }
+
+    #[test]
+    fn in_place_test() {
+        let grids: Vec<DenseStore<char>> = vec![
+            "abc\ndef".parse().unwrap(),
+            "abcd\nefgh\nijkl\nmnop".parse().unwrap(),
+            "abcde\nfghij\nklmno\npqrst\nuvwxy\nz0123\n45678"
+                .parse::<DenseStore<char>>()
+                .unwrap()
+                .with_origin(Point::new(-3, 2)),
+            "a".parse().unwrap(),
+            "".parse().unwrap(),
+        ];
+        for grid in grids {
+            for t in Transform::ALL.iter() {
+                let mut g = grid.clone();
+                g.transform_in_place(*t);
+                assert_eq!(g, grid.transformed(*t), "{:?}", t);
+            }
+        }
+
+        let mut grid: DenseStore<char> = "abc\ndef".parse().unwrap();
+        grid.rotate_cw();
+        assert_eq!(grid.to_string(), "da\neb\nfc");
+        grid.transpose();
+        assert_eq!(grid.to_string(), "def\nabc");
+        grid.flip_y();
+        grid.flip_x();
+        assert_eq!(grid.to_string(), "cba\nfed");
+    }
+}
@@ -0,0 +1,316 @@
+use std::fmt::Write as _;
+#[cfg(feature = "tui")]
+use std::io::{self, Write};
+#[cfg(feature = "tui")]
+use std::time::Duration;
+
+#[cfg(feature = "tui")]
+use crossterm::event::{self, Event, KeyCode};
+#[cfg(feature = "tui")]
+use crossterm::{cursor, terminal, QueueableCommand};
+
+#[cfg(feature = "tui")]
+use crate::{Adjacency, Automaton};
+use crate::{Grid, Point, Rect, Rgb};
+
+/// The window of grid coordinates shown on screen, which can be scrolled over a grid larger than
+/// the terminal, or one with no fixed bounds.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Viewport {
+    origin: Point,
+    width: usize,
+    height: usize,
+}
+
+impl Viewport {
+    /// A window of the given size with its top left corner at the origin.
+    pub fn new(width: usize, height: usize) -> Self {
+        Viewport {
+            origin: Point::default(),
+            width,
+            height,
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::with_size(self.origin, self.width, self.height)
+    }
+
+    /// Changes the size, keeping the top left corner in place.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+    }
+
+    pub fn scroll(&mut self, by: Point) {
+        self.origin += by;
+    }
+
+    /// Moves the window so its centre is over the centre of `rect`.
+    pub fn centre_on(&mut self, rect: Rect) {
+        let half = |n: usize| (n / 2) as isize;
+        let centre = rect.min + Point::new(half(rect.width()), half(rect.height()));
+        self.origin = centre - Point::new(half(self.width), half(self.height));
+    }
+}
+
+/// Draws the cells of `grid` inside `rect` as lines of text, coloured with 24-bit ANSI escapes.
+/// Cells missing from the grid are blank, and lines are separated by `\r\n` so the frame also
+/// draws correctly on a terminal in raw mode.
+pub fn ansi_frame<G, F>(grid: &G, rect: Rect, mut palette: F) -> String
+where
+    G: Grid,
+    F: FnMut(&G::Glyph) -> (char, Rgb),
+{
+    let mut out = String::new();
+    for y in rect.min.y..=rect.max.y {
+        if y != rect.min.y {
+            out.push_str("\r\n");
+        }
+        // Only switch colour where it changes, which keeps frames of large areas small.
+        let mut current = None;
+        for x in rect.min.x..=rect.max.x {
+            match grid.at(Point::new(x, y)) {
+                Some(g) => {
+                    let (ch, colour) = palette(g);
+                    if current != Some(colour) {
+                        let Rgb(r, g, b) = colour;
+                        write!(out, "\x1b[38;2;{};{};{}m", r, g, b).unwrap();
+                        current = Some(colour);
+                    }
+                    out.push(ch);
+                }
+                None => out.push(' '),
+            }
+        }
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+/// Plays an automaton in the terminal, redrawing the grid in place each generation.
+///
+/// Keys: space pauses and resumes, `n` steps once, `+` and `-` change the speed, the arrow and
+/// page keys scroll, `c` centres the grid and `q` or escape quits. Playback pauses by itself
+/// once the automaton stops changing.
+#[cfg(feature = "tui")]
+#[derive(Debug, Clone)]
+pub struct Player {
+    delay: Duration,
+    paused: bool,
+}
+
+#[cfg(feature = "tui")]
+enum Action {
+    Quit,
+    Step,
+    Redraw,
+}
+
+#[cfg(feature = "tui")]
+impl Player {
+    /// Plays with `delay` between generations.
+    pub fn new(delay: Duration) -> Self {
+        Player {
+            delay,
+            paused: false,
+        }
+    }
+
+    /// Waits for a key before the first step.
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    /// Runs the automaton on screen until the user quits, leaving it at whatever generation was
+    /// reached.
+    pub fn play<G, N, R, F>(
+        &mut self,
+        automaton: &mut Automaton<G, N, R>,
+        mut palette: F,
+    ) -> io::Result<()>
+    where
+        G: Grid + Clone,
+        G::Glyph: PartialEq,
+        N: Adjacency,
+        R: FnMut(&G::Glyph, &mut dyn Iterator<Item = &G::Glyph>) -> G::Glyph,
+        F: FnMut(&G::Glyph) -> (char, Rgb),
+    {
+        let _screen = Screen::enter()?;
+        let mut out = io::stdout();
+        let (cols, rows) = terminal::size()?;
+        let mut view = Viewport::new(cols as usize, rows.max(2) as usize - 1);
+        view.centre_on(automaton.grid().bounds());
+        let mut changed = None;
+
+        loop {
+            let stable = changed == Some(0);
+            let frame = ansi_frame(automaton.grid(), view.rect(), &mut palette);
+            let status = self.status(automaton.generation(), changed, &view);
+            out.queue(cursor::MoveTo(0, 0))?;
+            write!(out, "{}\r\n", frame)?;
+            out.queue(terminal::Clear(terminal::ClearType::CurrentLine))?;
+            write!(
+                out,
+                "{}",
+                status.chars().take(view.width).collect::<String>()
+            )?;
+            out.flush()?;
+
+            match self.input(&mut view, automaton.grid().bounds(), stable)? {
+                Action::Quit => return Ok(()),
+                Action::Step if !stable => changed = Some(automaton.step()),
+                _ => {}
+            }
+        }
+    }
+
+    fn status(&self, generation: usize, changed: Option<usize>, view: &Viewport) -> String {
+        let state = match changed {
+            Some(0) => "stable",
+            _ if self.paused => "paused",
+            _ => "running",
+        };
+        format!(
+            "gen {}  changed {}  {}  {}ms  at {},{}  [space] pause [n] step [+/-] speed [arrows] scroll [c] centre [q] quit",
+            generation,
+            changed.map_or("-".to_string(), |n| n.to_string()),
+            state,
+            self.delay.as_millis(),
+            view.origin.x,
+            view.origin.y,
+        )
+    }
+
+    /// Waits for a key, or until it's time for the next generation.
+    fn input(&mut self, view: &mut Viewport, bounds: Rect, stable: bool) -> io::Result<Action> {
+        if !self.paused && !stable && !event::poll(self.delay)? {
+            return Ok(Action::Step);
+        }
+        let page = view.height as isize;
+        let action = match event::read()? {
+            Event::Key(key) => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+                KeyCode::Char(' ') => {
+                    self.paused = !self.paused;
+                    Action::Redraw
+                }
+                KeyCode::Char('n') => Action::Step,
+                KeyCode::Char('+') | KeyCode::Char('=') => {
+                    self.delay = (self.delay / 2).max(Duration::from_millis(1));
+                    Action::Redraw
+                }
+                KeyCode::Char('-') => {
+                    self.delay = (self.delay * 2).min(Duration::from_secs(5));
+                    Action::Redraw
+                }
+                KeyCode::Char('c') => {
+                    view.centre_on(bounds);
+                    Action::Redraw
+                }
+                KeyCode::Left => scroll(view, -1, 0),
+                KeyCode::Right => scroll(view, 1, 0),
+                KeyCode::Up => scroll(view, 0, -1),
+                KeyCode::Down => scroll(view, 0, 1),
+                KeyCode::PageUp => scroll(view, 0, -page),
+                KeyCode::PageDown => scroll(view, 0, page),
+                _ => Action::Redraw,
+            },
+            Event::Resize(cols, rows) => {
+                view.resize(cols as usize, rows.max(2) as usize - 1);
+                // Whatever was on screen outside the new size would otherwise be left behind.
+                io::stdout().queue(terminal::Clear(terminal::ClearType::All))?;
+                Action::Redraw
+            }
+            Event::Mouse(_) => Action::Redraw,
+        };
+        Ok(action)
+    }
+}
+
+#[cfg(feature = "tui")]
+fn scroll(view: &mut Viewport, x: isize, y: isize) -> Action {
+    view.scroll(Point::new(x, y));
+    Action::Redraw
+}
+
+/// Raw mode on an alternate screen with the cursor hidden, for as long as it's held. The
+/// terminal is put back however the player exits.
+#[cfg(feature = "tui")]
+struct Screen;
+
+#[cfg(feature = "tui")]
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut out = io::stdout();
+        out.queue(terminal::EnterAlternateScreen)?
+            .queue(cursor::Hide)?
+            .flush()?;
+        Ok(Screen)
+    }
+}
+
+#[cfg(feature = "tui")]
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = out
+            .queue(cursor::Show)
+            .and_then(|o| o.queue(terminal::LeaveAlternateScreen))
+            .and_then(|o| o.flush());
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ansi_frame, DenseStore, Grid, Point, Rect, Rgb, SparseGrid, Viewport};
+
+    fn palette(c: &char) -> (char, Rgb) {
+        match c {
+            '#' => ('#', Rgb(255, 0, 0)),
+            _ => (*c, Rgb(0, 0, 255)),
+        }
+    }
+
+    #[test]
+    fn frame_test() {
+        let grid: DenseStore<char> = "#.\n..".parse().unwrap();
+        let frame = ansi_frame(&grid, grid.bounds(), palette);
+        assert_eq!(
+            frame,
+            "\x1b[38;2;255;0;0m#\x1b[38;2;0;0;255m.\x1b[0m\r\n\x1b[38;2;0;0;255m..\x1b[0m"
+        );
+
+        // Outside the grid is blank.
+        let rect = Rect::new(Point::new(1, 1), Point::new(2, 1));
+        assert_eq!(
+            ansi_frame(&grid, rect, palette),
+            "\x1b[38;2;0;0;255m. \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn viewport_test() {
+        let mut view = Viewport::new(4, 2);
+        assert_eq!(view.rect(), Rect::new(Point::new(0, 0), Point::new(3, 1)));
+
+        let mut sparse = SparseGrid::new('.');
+        sparse.set(Point::new(-10, -10), '#');
+        sparse.set(Point::new(10, 10), '#');
+        view.centre_on(sparse.bounds());
+        assert_eq!(view.rect(), Rect::new(Point::new(-2, -1), Point::new(1, 0)));
+
+        view.scroll(Point::new(10, 10));
+        view.resize(1, 1);
+        assert_eq!(view.rect(), Rect::new(Point::new(8, 9), Point::new(8, 9)));
+        let frame = ansi_frame(
+            &sparse,
+            Rect::new(Point::new(9, 10), Point::new(10, 10)),
+            palette,
+        );
+        assert_eq!(frame, "\x1b[38;2;0;0;255m.\x1b[38;2;255;0;0m#\x1b[0m");
+    }
+}
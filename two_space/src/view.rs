@@ -0,0 +1,327 @@
+use crate::{DenseStore, Grid, Point, Rect};
+
+/// One side of a rectangle.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl Side {
+    /// Clockwise from the top.
+    pub const ALL: [Side; 4] = [Side::Top, Side::Right, Side::Bottom, Side::Left];
+
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Top => Side::Bottom,
+            Side::Right => Side::Left,
+            Side::Bottom => Side::Top,
+            Side::Left => Side::Right,
+        }
+    }
+}
+
+/// A read-only rectangular window onto a grid, borrowing rather than copying its glyphs. Points
+/// are relative to the view, so its top left cell is the origin whatever part of the grid it
+/// shows. A view can also be transposed, so its rows are the grid's columns.
+pub struct View<'a, G> {
+    grid: &'a G,
+    rect: Rect,
+    transposed: bool,
+}
+
+impl<'a, G> Clone for View<'a, G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, G> Copy for View<'a, G> {}
+
+impl<'a, G: Grid> View<'a, G> {
+    /// The whole of a grid.
+    pub fn new(grid: &'a G) -> Self {
+        View {
+            grid,
+            rect: grid.bounds(),
+            transposed: false,
+        }
+    }
+
+    /// The same cells with rows and columns swapped.
+    pub fn transposed(&self) -> Self {
+        View {
+            transposed: !self.transposed,
+            ..*self
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        if self.transposed {
+            self.rect.height()
+        } else {
+            self.rect.width()
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        if self.transposed {
+            self.rect.width()
+        } else {
+            self.rect.height()
+        }
+    }
+
+    /// Turns a point in the view's coordinates into an offset from the top left of `rect`.
+    fn unview(&self, p: Point) -> Point {
+        if self.transposed {
+            Point::new(p.y, p.x)
+        } else {
+            p
+        }
+    }
+
+    /// The view's own coordinates, from the origin.
+    pub fn bounds(&self) -> Rect {
+        Rect::with_size(Point::default(), self.width(), self.height())
+    }
+
+    /// The part of the underlying grid shown, in the grid's coordinates.
+    pub fn source(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn at(&self, p: Point) -> Option<&'a G::Glyph> {
+        if self.bounds().contains(p) {
+            self.grid.at(self.rect.min + self.unview(p))
+        } else {
+            None
+        }
+    }
+
+    /// A smaller view of the part of this one inside `rect`, given in this view's coordinates.
+    pub fn crop(&self, rect: Rect) -> Self {
+        let rect = Rect::new(
+            rect.min.component_max(Point::default()),
+            rect.max.component_min(self.bounds().max),
+        );
+        let (a, b) = (self.unview(rect.min), self.unview(rect.max));
+        View {
+            rect: Rect::new(self.rect.min + a, self.rect.min + b),
+            ..*self
+        }
+    }
+
+    /// The view with `n` cells trimmed from every side.
+    pub fn inner(&self, n: usize) -> Self {
+        self.crop(self.bounds().expand(-(n as isize)))
+    }
+
+    /// Row `y`, left to right.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &'a G::Glyph> {
+        let view = *self;
+        (0..self.width()).filter_map(move |x| view.at(Point::new(x as isize, y as isize)))
+    }
+
+    /// Column `x`, top to bottom.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &'a G::Glyph> {
+        let view = *self;
+        (0..self.height()).filter_map(move |y| view.at(Point::new(x as isize, y as isize)))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &'a G::Glyph>> {
+        let view = *self;
+        (0..self.height()).map(move |y| view.row(y))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &'a G::Glyph>> {
+        let view = *self;
+        (0..self.width()).map(move |x| view.column(x))
+    }
+
+    /// The cells with `x - y == d`, running down and to the right. `d` is from `1 - height` at
+    /// the bottom left corner to `width - 1` at the top right.
+    pub fn diagonal(&self, d: isize) -> impl Iterator<Item = &'a G::Glyph> {
+        let view = *self;
+        let top = (-d).max(0);
+        (top..self.height() as isize)
+            .map(move |y| Point::new(y + d, y))
+            .take_while(move |p| p.x < view.width() as isize)
+            .filter_map(move |p| view.at(p))
+    }
+
+    /// The cells with `x + y == s`, running down and to the left. `s` is from 0 at the top left
+    /// corner to `width + height - 2` at the bottom right.
+    pub fn anti_diagonal(&self, s: usize) -> impl Iterator<Item = &'a G::Glyph> {
+        let view = *self;
+        let s = s as isize;
+        let top = (s - self.width() as isize + 1).max(0);
+        (top..=s.min(self.height() as isize - 1)).filter_map(move |y| view.at(Point::new(s - y, y)))
+    }
+
+    /// Every diagonal, from the bottom left corner to the top right.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &'a G::Glyph>> {
+        let view = *self;
+        (1 - self.height() as isize..self.width() as isize).map(move |d| view.diagonal(d))
+    }
+
+    /// Every anti-diagonal, from the top left corner to the bottom right.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &'a G::Glyph>> {
+        let view = *self;
+        let count = (self.width() + self.height()).saturating_sub(1);
+        (0..count).map(move |s| view.anti_diagonal(s))
+    }
+
+    /// The cells along one edge, left to right or top to bottom.
+    pub fn border(&self, side: Side) -> impl Iterator<Item = &'a G::Glyph> {
+        let (last_x, last_y) = (self.width().max(1) - 1, self.height().max(1) - 1);
+        let (horizontal, at) = match side {
+            Side::Top => (true, 0),
+            Side::Bottom => (true, last_y),
+            Side::Left => (false, 0),
+            Side::Right => (false, last_x),
+        };
+        let view = *self;
+        let len = if horizontal {
+            self.width()
+        } else {
+            self.height()
+        };
+        (0..len).filter_map(move |i| {
+            let p = if horizontal {
+                Point::new(i as isize, at as isize)
+            } else {
+                Point::new(at as isize, i as isize)
+            };
+            view.at(p)
+        })
+    }
+
+    /// Cuts the view into tiles of the given size, row by row. Tiles on the right and bottom
+    /// edges are smaller if the size doesn't divide the view evenly.
+    pub fn split(&self, tile_width: usize, tile_height: usize) -> Vec<Vec<Self>> {
+        assert!(tile_width > 0 && tile_height > 0, "Empty tiles");
+        (0..self.height())
+            .step_by(tile_height)
+            .map(|y| {
+                (0..self.width())
+                    .step_by(tile_width)
+                    .map(|x| {
+                        let origin = Point::new(x as isize, y as isize);
+                        self.crop(Rect::with_size(origin, tile_width, tile_height))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Copies the glyphs into a new grid.
+    pub fn to_dense(&self) -> DenseStore<G::Glyph>
+    where
+        G::Glyph: Clone,
+    {
+        let rows: Vec<Vec<G::Glyph>> = self.rows().map(|row| row.cloned().collect()).collect();
+        DenseStore::new(&rows)
+    }
+}
+
+/// Joins an arrangement of views into one grid, like laying out a jigsaw. Views in a row must
+/// share a height, and each row must come out the same width.
+pub fn stitch<G>(tiles: &[Vec<View<'_, G>>]) -> DenseStore<G::Glyph>
+where
+    G: Grid,
+    G::Glyph: Clone,
+{
+    let mut rows: Vec<Vec<G::Glyph>> = Vec::new();
+    for tile_row in tiles {
+        let height = tile_row.first().map_or(0, |t| t.height());
+        assert!(
+            tile_row.iter().all(|t| t.height() == height),
+            "Mismatched tile heights"
+        );
+        for y in 0..height {
+            rows.push(tile_row.iter().flat_map(|t| t.row(y)).cloned().collect());
+        }
+    }
+    assert!(
+        rows.iter().all(|r| r.len() == rows[0].len()),
+        "Mismatched tile widths"
+    );
+    DenseStore::new(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{stitch, DenseStore, Point, Rect, Side, View};
+
+    fn chars<'a, I: Iterator<Item = &'a char>>(it: I) -> String {
+        it.collect()
+    }
+
+    #[test]
+    fn view_test() {
+        let grid: DenseStore<char> = "abcd\nefgh\nijkl".parse().unwrap();
+        let all = View::new(&grid);
+        assert_eq!(chars(all.border(Side::Top)), "abcd");
+        assert_eq!(chars(all.border(Side::Right)), "dhl");
+        assert_eq!(chars(all.border(Side::Bottom)), "ijkl");
+        assert_eq!(chars(all.border(Side::Left)), "aei");
+        assert_eq!(chars(all.column(1)), "bfj");
+
+        let inner = all.inner(1);
+        assert_eq!((inner.width(), inner.height()), (2, 1));
+        assert_eq!(inner.at(Point::new(0, 0)), Some(&'f'));
+        assert_eq!(inner.at(Point::new(0, 1)), None);
+        assert_eq!(
+            inner.source(),
+            Rect::new(Point::new(1, 1), Point::new(2, 1))
+        );
+
+        let corner = all.crop(Rect::new(Point::new(2, 1), Point::new(9, 9)));
+        assert_eq!(corner.to_dense(), "gh\nkl".parse().unwrap());
+        let cols: Vec<String> = corner.columns().map(chars).collect();
+        assert_eq!(cols, vec!["gk", "hl"]);
+    }
+
+    #[test]
+    fn diagonal_test() {
+        let grid: DenseStore<char> = "abcd\nefgh\nijkl".parse().unwrap();
+        let all = View::new(&grid);
+        let diagonals: Vec<String> = all.diagonals().map(chars).collect();
+        assert_eq!(diagonals, vec!["i", "ej", "afk", "bgl", "ch", "d"]);
+        let anti: Vec<String> = all.anti_diagonals().map(chars).collect();
+        assert_eq!(anti, vec!["a", "be", "cfi", "dgj", "hk", "l"]);
+        assert_eq!(chars(all.diagonal(5)), "");
+        assert_eq!(chars(all.anti_diagonal(9)), "");
+
+        let tall = all.transposed();
+        assert_eq!((tall.width(), tall.height()), (3, 4));
+        let rows: Vec<String> = tall.rows().map(chars).collect();
+        assert_eq!(rows, vec!["aei", "bfj", "cgk", "dhl"]);
+        assert_eq!(chars(tall.border(Side::Right)), "ijkl");
+        assert_eq!(chars(tall.diagonal(0)), "afk");
+        assert_eq!(tall.to_dense(), "aei\nbfj\ncgk\ndhl".parse().unwrap());
+        assert_eq!(tall.transposed().to_dense(), grid);
+
+        // Cropping a transposed view picks out the transposed part of the grid.
+        let part = tall.crop(Rect::new(Point::new(1, 2), Point::new(2, 3)));
+        assert_eq!(part.to_dense(), "gk\nhl".parse().unwrap());
+        assert_eq!(part.source(), Rect::new(Point::new(2, 1), Point::new(3, 2)));
+    }
+
+    #[test]
+    fn stitch_test() {
+        let grid: DenseStore<char> = "abcde\nfghij\nklmno\npqrst".parse().unwrap();
+        let tiles = View::new(&grid).split(2, 2);
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(tiles[0].len(), 3);
+        assert_eq!(tiles[1][2].to_dense(), "o\nt".parse().unwrap());
+        assert_eq!(stitch(&tiles), grid);
+
+        // Swap the tile rows, and drop the narrow column.
+        let swapped = vec![tiles[1][..2].to_vec(), tiles[0][..2].to_vec()];
+        assert_eq!(stitch(&swapped), "klmn\npqrs\nabcd\nfghi".parse().unwrap());
+    }
+}
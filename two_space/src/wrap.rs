@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+
+use crate::{Grid, Point, Rect};
+
+/// What lies past the edge of a grid along one axis.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Edge {
+    /// Nothing; the grid ends there.
+    Bounded,
+    /// The grid repeats forever.
+    Repeat,
+    /// The edge row or column extends forever.
+    Clamp,
+}
+
+impl Edge {
+    fn map(&self, v: isize, min: isize, max: isize) -> isize {
+        match self {
+            Edge::Bounded => v,
+            Edge::Repeat => min + (v - min).rem_euclid(max - min + 1),
+            Edge::Clamp => v.max(min).min(max),
+        }
+    }
+}
+
+/// Wraps a grid so that reads beyond its bounds are redirected per axis according to an `Edge`,
+/// and optionally fall back to a default glyph if they still miss. Writes go through to the inner
+/// grid at the redirected position; the default glyph itself can't be written.
+///
+/// `bounds` stays that of the inner grid, but a point counts as part of the grid wherever it is
+/// redirected onto an inner cell, so neighbours on a torus reach across the edges. Rays stop at a
+/// `Bounded` edge, or once they come back to a cell they have already passed.
+#[derive(Debug, Clone)]
+pub struct Wrapped<G: Grid> {
+    inner: G,
+    x: Edge,
+    y: Edge,
+    default: Option<G::Glyph>,
+}
+
+impl<G: Grid> Wrapped<G> {
+    pub fn new(inner: G, x: Edge, y: Edge) -> Self {
+        Wrapped {
+            inner,
+            x,
+            y,
+            default: None,
+        }
+    }
+
+    /// Repeats horizontally but not vertically, like a forest hillside.
+    pub fn repeat_x(inner: G) -> Self {
+        Self::new(inner, Edge::Repeat, Edge::Bounded)
+    }
+
+    /// Repeats on both axes.
+    pub fn torus(inner: G) -> Self {
+        Self::new(inner, Edge::Repeat, Edge::Repeat)
+    }
+
+    /// Extends the edges on both axes.
+    pub fn clamped(inner: G) -> Self {
+        Self::new(inner, Edge::Clamp, Edge::Clamp)
+    }
+
+    /// Reads that miss the inner grid return `default` instead of `None`.
+    pub fn with_default(mut self, default: G::Glyph) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    pub fn inner(&self) -> &G {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> G {
+        self.inner
+    }
+
+    /// The inner grid position that `p` reads from.
+    pub fn wrap(&self, p: Point) -> Option<Point> {
+        let Rect { min, max } = self.inner.bounds();
+        if self.inner.bounds().is_empty() {
+            return None;
+        }
+        Some(Point::new(
+            self.x.map(p.x, min.x, max.x),
+            self.y.map(p.y, min.y, max.y),
+        ))
+    }
+}
+
+impl<G: Grid> Grid for Wrapped<G> {
+    type Glyph = G::Glyph;
+
+    fn at(&self, p: Point) -> Option<&G::Glyph> {
+        self.wrap(p)
+            .and_then(|q| self.inner.at(q))
+            .or(self.default.as_ref())
+    }
+
+    fn at_mut(&mut self, p: Point) -> Option<&mut G::Glyph> {
+        let q = self.wrap(p)?;
+        self.inner.at_mut(q)
+    }
+
+    fn bounds(&self) -> Rect {
+        self.inner.bounds()
+    }
+
+    fn contains(&self, p: Point) -> bool {
+        self.wrap(p).is_some_and(|q| self.inner.contains(q))
+    }
+
+    fn ray(&self, from: Point, dir: Point) -> impl Iterator<Item = (Point, &G::Glyph)> + '_ {
+        // Every point is inside a torus, so stop on coming back round instead.
+        let mut seen: HashSet<Point> = self.wrap(from).into_iter().collect();
+        let mut p = from;
+        std::iter::from_fn(move || {
+            if dir == Point::default() {
+                return None;
+            }
+            p += dir;
+            let q = self.wrap(p).filter(|&q| self.inner.contains(q))?;
+            if seen.insert(q) {
+                self.inner.at(q).map(|g| (p, g))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Automaton, DenseStore, Edge, Grid, Neighborhood, Point, Wrapped};
+
+    #[test]
+    fn wrap_test() {
+        let grid: DenseStore<char> = "ab\ncd".parse().unwrap();
+        let at = |g: &Wrapped<DenseStore<char>>, x, y| g.at(Point::new(x, y)).copied();
+
+        let hill = Wrapped::repeat_x(grid.clone());
+        assert_eq!(at(&hill, 5, 1), Some('d'));
+        assert_eq!(at(&hill, -1, 0), Some('b'));
+        assert_eq!(at(&hill, 0, 2), None);
+
+        let torus = Wrapped::torus(grid.clone());
+        assert_eq!(at(&torus, -3, -3), Some('d'));
+
+        let clamped = Wrapped::clamped(grid.clone());
+        assert_eq!(at(&clamped, -10, 10), Some('c'));
+
+        let mut fenced = Wrapped::new(grid, Edge::Bounded, Edge::Repeat).with_default('#');
+        assert_eq!(at(&fenced, 1, 3), Some('d'));
+        assert_eq!(at(&fenced, 2, 0), Some('#'));
+
+        assert!(fenced.set(Point::new(0, 5), 'z'));
+        assert!(!fenced.set(Point::new(9, 0), 'z'));
+        assert_eq!(fenced.inner(), &"ab\nzd".parse().unwrap());
+    }
+
+    #[test]
+    fn torus_neighbors_test() {
+        let grid: DenseStore<char> = "abc\ndef\nghi".parse().unwrap();
+        let corner = Point::new(0, 0);
+        assert_eq!(grid.neighbors(corner, Neighborhood::King).count(), 3);
+
+        let torus = Wrapped::torus(grid.clone());
+        let around: String = torus
+            .neighbors(corner, Neighborhood::King)
+            .map(|(_, &g)| g)
+            .collect();
+        assert_eq!(around.len(), 8);
+        assert!(around.contains('i') && around.contains('c') && around.contains('g'));
+
+        // Rays go round once and stop, rather than forever.
+        let ray: String = torus
+            .ray(corner, Point::new(1, 0))
+            .map(|(_, &g)| g)
+            .collect();
+        assert_eq!(ray, "bc");
+        assert_eq!(torus.ray(corner, Point::new(1, 1)).count(), 2);
+        assert_eq!(
+            torus.first_in_direction(corner, Point::new(0, 1), |&g| g == 'z'),
+            None
+        );
+        let clamped = Wrapped::clamped(grid.clone());
+        assert_eq!(clamped.ray(corner, Point::new(-1, 0)).count(), 0);
+        assert_eq!(clamped.ray(corner, Point::new(1, 0)).count(), 2);
+
+        // A default glyph past a bounded edge isn't part of the grid.
+        let fenced = Wrapped::new(grid, Edge::Bounded, Edge::Bounded).with_default('#');
+        assert_eq!(fenced.neighbors(corner, Neighborhood::King).count(), 3);
+        assert_eq!(fenced.ray(corner, Point::new(0, 1)).count(), 2);
+        // A glider crossing the edges of a torus comes back to where it started.
+        let life = |g: &char, n: &mut dyn Iterator<Item = &char>| match (
+            g,
+            n.filter(|&&c| c == '#').count(),
+        ) {
+            (_, 3) | ('#', 2) => '#',
+            _ => '.',
+        };
+        let glider: DenseStore<char> = ".#...\n..#..\n###..\n.....\n.....".parse().unwrap();
+        let mut a = Automaton::new(Wrapped::torus(glider.clone()), Neighborhood::King, life);
+        a.run(20);
+        assert_eq!(a.grid().inner(), &glider);
+    }
+}